@@ -1,9 +1,6 @@
-
-use rspamd_base32;
-
 use criterion::{black_box, criterion_group, criterion_main,
                 BenchmarkId, Bencher, Criterion, Throughput};
-use rspamd_base32::{encode, decode, encode_alphabet_slice};
+use rspamd_base32::{encode, decode, decode_alphabet_vec, encode_alphabet_slice, encode_small};
 use rspamd_base32::alphabet::ZBASE32;
 use rspamd_base32::encode::encoded_len;
 use rand::prelude::*;
@@ -28,6 +25,18 @@ fn do_decode_bench(b: &mut Bencher, &size: &usize) {
     });
 }
 
+fn do_decode_vec_bench(b: &mut Bencher, &size: &usize) {
+    let mut v: Vec<u8> = Vec::with_capacity(size * 5 / 8);
+    fill_buf(&mut v);
+    let encoded = encode(&v);
+    let mut buf = Vec::new();
+
+    b.iter(|| {
+        decode_alphabet_vec(&encoded, &mut buf, &ZBASE32).unwrap();
+        black_box(&buf);
+    });
+}
+
 fn do_encode_bench(b: &mut Bencher, &size: &usize) {
     let mut v: Vec<u8> = Vec::with_capacity(size * 5 / 8);
     fill_buf(&mut v);
@@ -39,6 +48,28 @@ fn do_encode_bench(b: &mut Bencher, &size: &usize) {
     });
 }
 
+fn do_encode_short_bench(b: &mut Bencher, &size: &usize) {
+    let mut v: Vec<u8> = Vec::with_capacity(size);
+    fill_buf(&mut v);
+    let mut buf = vec![0; encoded_len(v.len()).expect("bad size")];
+
+    b.iter(|| {
+        encode_alphabet_slice(&v, buf.as_mut_slice(), &ZBASE32);
+        black_box(&buf);
+    });
+}
+
+fn do_encode_small_bench(b: &mut Bencher, &size: &usize) {
+    let mut v: Vec<u8> = Vec::with_capacity(size);
+    fill_buf(&mut v);
+    let mut buf = vec![0; encoded_len(v.len()).expect("bad size")];
+
+    b.iter(|| {
+        encode_small(&v, buf.as_mut_slice(), &ZBASE32);
+        black_box(&buf);
+    });
+}
+
 const SIZES: [usize; 5] = [10, 128, 1024, 12400, 1024 * 1024 * 2];
 
 fn bench_decode(c: &mut Criterion) {
@@ -48,6 +79,9 @@ fn bench_decode(c: &mut Criterion) {
         group.bench_with_input(BenchmarkId::new("decode bench", sz),
                            sz,
                            do_decode_bench);
+        group.bench_with_input(BenchmarkId::new("decode vec bench", sz),
+                           sz,
+                           do_decode_vec_bench);
     }
 }
 
@@ -61,5 +95,22 @@ fn bench_encode(c: &mut Criterion) {
     }
 }
 
-criterion_group!(benches, bench_decode, bench_encode);
+// Input sizes of typical hashes and keys, encode_small is compared with
+// the byte by byte loop of encode_alphabet_slice
+const SMALL_SIZES: [usize; 4] = [8, 16, 20, 32];
+
+fn bench_encode_small(c: &mut Criterion) {
+    let mut group = c.benchmark_group("bench_encode_small");
+    for sz in SMALL_SIZES.iter() {
+        group.throughput(Throughput::Bytes(*sz as u64));
+        group.bench_with_input(BenchmarkId::new("encode slice bench", sz),
+                               sz,
+                               do_encode_short_bench);
+        group.bench_with_input(BenchmarkId::new("encode small bench", sz),
+                               sz,
+                               do_encode_small_bench);
+    }
+}
+
+criterion_group!(benches, bench_decode, bench_encode, bench_encode_small);
 criterion_main!(benches);
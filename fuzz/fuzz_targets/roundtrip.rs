@@ -0,0 +1,34 @@
+//! Run with `cargo +nightly fuzz run roundtrip`, a bounded version of the same
+//! properties runs as part of `cargo test`
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use rspamd_base32::alphabet::*;
+use rspamd_base32::{decode_alphabet, encode_alphabet};
+
+const SHIPPED: [&Alphabet; 10] = [
+    &ZBASE32,
+    &ZBASE32_CANONICAL,
+    &BECH32,
+    &RFC,
+    &RFC_LOWER,
+    &DNS32,
+    &BASE32HEX,
+    &CROCKFORD,
+    &WORDSAFE,
+    &GEOHASH,
+];
+
+fuzz_target!(|data: &[u8]| {
+    for alphabet in SHIPPED {
+        // decode(encode(x)) == x for any octets
+        let encoded = encode_alphabet(data, alphabet);
+        assert_eq!(data, &decode_alphabet(&encoded, alphabet).unwrap()[..]);
+
+        // Arbitrary input either fails to decode or re-encodes to a stable form
+        if let Ok(decoded) = decode_alphabet(data, alphabet) {
+            let reencoded = encode_alphabet(&decoded, alphabet);
+            assert_eq!(decoded, decode_alphabet(&reencoded, alphabet).unwrap());
+        }
+    }
+});
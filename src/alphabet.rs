@@ -1,46 +1,422 @@
 //! Provides Alphabet used for Base32 encoding and decoding
-use std::{error, fmt};
+use core::cmp::Ordering;
+use core::hash::{Hash, Hasher};
+use core::{fmt, str};
+#[cfg(any(feature = "std", test))]
+use std::error;
 
 pub const ALPHABET_SIZE: usize = 32;
 
-#[derive(Clone, Debug, Eq, PartialEq)]
+/// Direction in which octet bits are packed into symbols
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, PartialOrd, Ord)]
+pub enum BitOrder {
+    /// Least significant bits first, as Rspamd does
+    LsbFirst,
+    /// Most significant bits first, as described by RFC 4648
+    MsbFirst,
+}
+
+/// Order in which octets of each 5-octet group are packed, independently of [BitOrder]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, PartialOrd, Ord)]
+pub enum OctetOrder {
+    /// Octets are packed as they appear in the input
+    Natural,
+    /// Octets of every group, including the trailing partial one, are packed last to first
+    Reversed,
+}
+
+/// Legacy packing order, each variant is a combination of [BitOrder] and [OctetOrder]
+#[deprecated(note = "use BitOrder and OctetOrder")]
+#[derive(Clone, Debug, Eq, PartialEq, Hash, PartialOrd, Ord)]
 pub enum EncodeOrder {
+    /// [BitOrder::LsbFirst] with [OctetOrder::Natural]
     OrderInversed,
+    /// [BitOrder::MsbFirst] with [OctetOrder::Natural]
     OrderNormal
 }
 
+#[allow(deprecated)]
+impl EncodeOrder {
+    /// Returns the bit order of this combination
+    pub const fn bit_order(&self) -> BitOrder {
+        match self {
+            EncodeOrder::OrderInversed => BitOrder::LsbFirst,
+            EncodeOrder::OrderNormal => BitOrder::MsbFirst,
+        }
+    }
+
+    /// Returns the octet order of this combination
+    pub const fn octet_order(&self) -> OctetOrder {
+        OctetOrder::Natural
+    }
+}
+
 /// Defines alphabet - 32 characters used for Base32
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Eq, PartialEq)]
 pub struct Alphabet {
     pub(crate) encode_symbols: [u8; ALPHABET_SIZE],
-    pub(crate) decode_bytes: [u8; u8::MAX as usize],
-    pub(crate) encode_order : EncodeOrder,
+    pub(crate) decode_bytes: [u8; 256],
+    pub(crate) bit_order: BitOrder,
+    pub(crate) octet_order: OctetOrder,
+    pub(crate) padding: Option<u8>,
 }
 
 impl Alphabet {
     /// Performs no checks so that it can be const.
     /// Used only for known-valid strings.
-    const fn from_str_unsafe(alphabet: &str, encode_order: EncodeOrder) -> Self {
+    const fn from_str_unsafe(alphabet: &str, bit_order: BitOrder) -> Self {
+        Self::from_parts_unsafe(alphabet, bit_order, OctetOrder::Natural, false, &[], None)
+    }
+
+    /// `aliases` are pairs of an extra byte and the symbol it is decoded as
+    const fn from_parts_unsafe(
+        alphabet: &str,
+        bit_order: BitOrder,
+        octet_order: OctetOrder,
+        case_insensitive: bool,
+        aliases: &[(u8, u8)],
+        padding: Option<u8>,
+    ) -> Self {
         let mut symbols = [0_u8; ALPHABET_SIZE];
         let source_bytes = alphabet.as_bytes();
-        let mut decode_bytes = [0xff_u8; u8::MAX as usize];
 
         let mut index = 0;
         while index < ALPHABET_SIZE {
-            let sym = source_bytes[index];
-            symbols[index] = sym;
-            decode_bytes[sym as usize] = index as u8;
-            index = index + 1;
+            symbols[index] = source_bytes[index];
+            index += 1;
         }
-        Alphabet { encode_symbols: symbols, decode_bytes, encode_order }
+
+        let decode_bytes = compile_decode_table(&symbols, case_insensitive, aliases);
+        Alphabet { encode_symbols: symbols, decode_bytes, bit_order, octet_order, padding }
     }
 
-    /// Checks input for printability and duplicates
+    /// Checks input for printability and duplicates, the order can be either, so custom
+    /// alphabets are compatible with Rspamd's inversed packing just as [ZBASE32] is
+    #[deprecated(note = "use AlphabetBuilder::bit_order and AlphabetBuilder::octet_order")]
+    #[allow(deprecated)]
     pub const fn from_str_order(alphabet: &str, encode_order: EncodeOrder) -> Result<Self, ParseAlphabetError> {
+        AlphabetBuilder::new()
+            .symbols(alphabet)
+            .bit_order(encode_order.bit_order())
+            .octet_order(encode_order.octet_order())
+            .build()
+    }
+
+    pub const fn from_str(alphabet: &str) -> Result<Self, ParseAlphabetError> {
+        AlphabetBuilder::new().symbols(alphabet).build()
+    }
+
+    /// Parses `order:symbols`, where order is `normal` for [BitOrder::MsbFirst] or `inversed`
+    /// for Rspamd compatible [BitOrder::LsbFirst], e.g. from a command line flag.
+    /// The symbols are validated as by [Alphabet::from_str]
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rspamd_base32::alphabet::{Alphabet, ZBASE32};
+    ///
+    /// assert_eq!(Ok(ZBASE32), Alphabet::from_spec("inversed:ybndrfg8ejkmcpqxot1uwisza345h769"));
+    /// ```
+    pub fn from_spec(spec: &str) -> Result<Self, ParseAlphabetError> {
+        let (order, symbols) = spec.split_once(':').ok_or(ParseAlphabetError::InvalidSpec)?;
+        let bit_order = match order {
+            "normal" => BitOrder::MsbFirst,
+            "inversed" => BitOrder::LsbFirst,
+            _ => return Err(ParseAlphabetError::InvalidSpec),
+        };
+
+        AlphabetBuilder::new()
+            .symbols(symbols)
+            .bit_order(bit_order)
+            .build()
+    }
+
+    /// Checks that input consists of lower case letters and digits only, so that encoded
+    /// strings are valid DNS labels, and builds a case insensitive normal order alphabet
+    pub const fn from_str_dns_safe(alphabet: &str) -> Result<Self, ParseAlphabetError> {
+        let source_bytes = alphabet.as_bytes();
+        let mut index = 0;
+        while index < source_bytes.len() {
+            let byte = source_bytes[index];
+            if !(byte.is_ascii_lowercase() || byte.is_ascii_digit()) {
+                return Err(ParseAlphabetError::DisallowedByte(byte));
+            }
+            index += 1;
+        }
+
+        AlphabetBuilder::new()
+            .symbols(alphabet)
+            .case_insensitive(true)
+            .build()
+    }
+
+    /// Returns 32 symbols used for encoding, indexed by their 5-bit value
+    pub const fn symbols(&self) -> &[u8; ALPHABET_SIZE] {
+        &self.encode_symbols
+    }
+
+    /// Returns the number of bits encoded by every symbol, `log2(ALPHABET_SIZE)`:
+    /// 8 symbols encode 5 octets, see [crate::encode::encoded_len]
+    pub const fn bits_per_symbol(&self) -> u32 {
+        ALPHABET_SIZE.trailing_zeros()
+    }
+
+    /// Returns the direction in which octet bits are packed into symbols
+    pub const fn bit_order(&self) -> BitOrder {
+        self.bit_order
+    }
+
+    /// Returns the order in which octets of each group are packed
+    pub const fn octet_order(&self) -> OctetOrder {
+        self.octet_order
+    }
+
+    /// Returns the same symbols with the opposite [BitOrder], e.g. [ZBASE32_RFC_ORDER] for [ZBASE32].
+    /// The decode table does not depend on the order, so it is copied as is
+    pub const fn invert(&self) -> Alphabet {
+        let bit_order = match self.bit_order {
+            BitOrder::LsbFirst => BitOrder::MsbFirst,
+            BitOrder::MsbFirst => BitOrder::LsbFirst,
+        };
+        Alphabet { bit_order, ..*self }
+    }
+
+    /// Returns the legacy order matching the bit order, it cannot express [OctetOrder::Reversed]
+    #[deprecated(note = "use Alphabet::bit_order and Alphabet::octet_order")]
+    #[allow(deprecated)]
+    pub const fn encode_order(&self) -> &EncodeOrder {
+        match self.bit_order {
+            BitOrder::LsbFirst => &EncodeOrder::OrderInversed,
+            BitOrder::MsbFirst => &EncodeOrder::OrderNormal,
+        }
+    }
+
+    /// Checks whether `byte` is accepted by the decoder, including case-folded
+    /// variants and aliases of the symbols
+    pub const fn contains(&self, byte: u8) -> bool {
+        self.decode_bytes[byte as usize] != 0xff
+    }
+
+    /// Checks whether a padding character was configured with [AlphabetBuilder::padding].
+    /// None of the built-in alphabets carry error control of their own, check symbols are
+    /// added on top of any alphabet by [crate::checkdigit] and [crate::bech32]
+    pub const fn has_padding(&self) -> bool {
+        self.padding.is_some()
+    }
+
+    /// Checks whether both alphabets encode the same values with the same symbols.
+    /// The encode order is not compared, e.g. [ZBASE32] and [ZBASE32_CANONICAL] have the same symbols
+    pub fn is_same_symbols(&self, other: &Alphabet) -> bool {
+        self.encode_symbols == other.encode_symbols
+    }
+}
+
+/// Builds the decoding table for `symbols`: every byte maps to the 5-bit value of the symbol
+/// it is decoded as, or `0xff` if it is not accepted.
+///
+/// With `case_insensitive` both ASCII cases of every symbol and alias are accepted.
+/// `aliases` are pairs of an extra byte and the symbol it is decoded as, e.g. visually
+/// confusable characters; aliases of bytes that are not symbols are not accepted.
+/// No validation is performed, see [AlphabetBuilder::build] for that.
+pub const fn compile_decode_table(
+    symbols: &[u8; ALPHABET_SIZE],
+    case_insensitive: bool,
+    aliases: &[(u8, u8)],
+) -> [u8; 256] {
+    let mut decode_bytes = [0xff_u8; 256];
+
+    let mut index = 0;
+    while index < ALPHABET_SIZE {
+        let sym = symbols[index];
+        decode_bytes[sym as usize] = index as u8;
+        if case_insensitive {
+            decode_bytes[sym.to_ascii_lowercase() as usize] = index as u8;
+            decode_bytes[sym.to_ascii_uppercase() as usize] = index as u8;
+        }
+        index += 1;
+    }
+
+    index = 0;
+    while index < aliases.len() {
+        let (alias, sym) = aliases[index];
+        let decoded = decode_bytes[sym as usize];
+        decode_bytes[alias as usize] = decoded;
+        if case_insensitive {
+            decode_bytes[alias.to_ascii_lowercase() as usize] = decoded;
+            decode_bytes[alias.to_ascii_uppercase() as usize] = decoded;
+        }
+        index += 1;
+    }
+
+    decode_bytes
+}
+
+/// Only symbols and order are hashed, the decoding table is derived from them
+impl Hash for Alphabet {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.encode_symbols.hash(state);
+        self.bit_order.hash(state);
+        self.octet_order.hash(state);
+    }
+}
+
+/// Alphabets are ordered by symbols, then by order, remaining settings such as
+/// aliases and padding only break ties to stay consistent with equality
+impl Ord for Alphabet {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.encode_symbols
+            .cmp(&other.encode_symbols)
+            .then_with(|| self.bit_order.cmp(&other.bit_order))
+            .then_with(|| self.octet_order.cmp(&other.octet_order))
+            .then_with(|| self.padding.cmp(&other.padding))
+            .then_with(|| self.decode_bytes.cmp(&other.decode_bytes))
+    }
+}
+
+impl PartialOrd for Alphabet {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Alphabet {
+    /// Symbols as a string, they are printable ASCII for every constructed alphabet
+    fn symbols_str(&self) -> &str {
+        str::from_utf8(&self.encode_symbols).expect("alphabet symbols are ASCII")
+    }
+}
+
+/// Shows the symbols and packing orders, e.g. `Alphabet("ABCDEFGHIJKLMNOPQRSTUVWXYZ234567", MsbFirst, Natural)`
+impl fmt::Display for Alphabet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Alphabet({:?}, {:?}, {:?})", self.symbols_str(), self.bit_order, self.octet_order)
+    }
+}
+
+/// The decoding table is derived from the other settings and is not shown
+impl fmt::Debug for Alphabet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Alphabet")
+            .field("symbols", &self.symbols_str())
+            .field("bit_order", &self.bit_order)
+            .field("octet_order", &self.octet_order)
+            .field("padding", &self.padding.map(char::from))
+            .finish_non_exhaustive()
+    }
+}
+
+/// [ZBASE32], the alphabet used by [crate::encode::encode] and [crate::decode::decode]
+impl Default for Alphabet {
+    fn default() -> Self {
+        ZBASE32
+    }
+}
+
+impl TryFrom<&str> for Alphabet {
+    type Error = ParseAlphabetError;
+
+    fn try_from(alphabet: &str) -> Result<Self, Self::Error> {
+        Alphabet::from_str(alphabet)
+    }
+}
+
+impl str::FromStr for Alphabet {
+    type Err = ParseAlphabetError;
+
+    fn from_str(alphabet: &str) -> Result<Self, Self::Err> {
+        Alphabet::from_str(alphabet)
+    }
+}
+
+/// Configures and validates a custom [Alphabet]
+///
+/// # Example
+///
+/// ```rust
+/// use rspamd_base32::alphabet::{AlphabetBuilder, BitOrder};
+///
+/// let alphabet = AlphabetBuilder::new()
+///     .symbols("abcdefghijklmnopqrstuvwxyz234567")
+///     .bit_order(BitOrder::MsbFirst)
+///     .case_insensitive(true)
+///     .build()
+///     .unwrap();
+/// assert_eq!(b"hello", &rspamd_base32::decode_alphabet("NBSWY3DP", &alphabet).unwrap()[..]);
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AlphabetBuilder<'a> {
+    symbols: &'a str,
+    bit_order: BitOrder,
+    octet_order: OctetOrder,
+    case_insensitive: bool,
+    aliases: &'a [(u8, u8)],
+    padding: Option<u8>,
+}
+
+impl<'a> AlphabetBuilder<'a> {
+    /// Creates a builder for a case sensitive, normal order alphabet without padding
+    pub const fn new() -> Self {
+        AlphabetBuilder {
+            symbols: "",
+            bit_order: BitOrder::MsbFirst,
+            octet_order: OctetOrder::Natural,
+            case_insensitive: false,
+            aliases: &[],
+            padding: None,
+        }
+    }
+
+    /// Sets 32 symbols used for encoding
+    pub const fn symbols(mut self, symbols: &'a str) -> Self {
+        self.symbols = symbols;
+        self
+    }
+
+    /// Sets both bit and octet order from the legacy combination
+    #[deprecated(note = "use AlphabetBuilder::bit_order and AlphabetBuilder::octet_order")]
+    #[allow(deprecated)]
+    pub const fn encode_order(self, encode_order: EncodeOrder) -> Self {
+        self.bit_order(encode_order.bit_order())
+            .octet_order(encode_order.octet_order())
+    }
+
+    /// Sets the direction in which octet bits are packed into symbols
+    pub const fn bit_order(mut self, bit_order: BitOrder) -> Self {
+        self.bit_order = bit_order;
+        self
+    }
+
+    /// Sets the order in which octets of each group are packed
+    pub const fn octet_order(mut self, octet_order: OctetOrder) -> Self {
+        self.octet_order = octet_order;
+        self
+    }
+
+    /// Decodes symbols regardless of their ASCII case
+    pub const fn case_insensitive(mut self, case_insensitive: bool) -> Self {
+        self.case_insensitive = case_insensitive;
+        self
+    }
+
+    /// Sets pairs of an extra byte and the symbol it is decoded as,
+    /// e.g. to accept visually confusable characters
+    pub const fn aliases(mut self, aliases: &'a [(u8, u8)]) -> Self {
+        self.aliases = aliases;
+        self
+    }
+
+    /// Sets the padding character recognised by padded decoding
+    pub const fn padding(mut self, padding: Option<u8>) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Checks symbols for printability and duplicates and builds an [Alphabet]
+    pub const fn build(self) -> Result<Alphabet, ParseAlphabetError> {
         const FIRST_PRINTABLE: u8 = 32;
         const LAST_PRINTABLE: u8 = 126;
-        const DUPS_SIZE: usize = (LAST_PRINTABLE - FIRST_PRINTABLE) as usize;
-        let source_bytes = alphabet.as_bytes();
+        const DUPS_SIZE: usize = (LAST_PRINTABLE - FIRST_PRINTABLE + 1) as usize;
+        let source_bytes = self.symbols.as_bytes();
         let mut dups : [bool; DUPS_SIZE] = [false; DUPS_SIZE];
 
         if source_bytes.len() != ALPHABET_SIZE {
@@ -51,28 +427,79 @@ impl Alphabet {
         while index < ALPHABET_SIZE {
             let byte = source_bytes[index];
             // Must be printable for sanity
-            if !(byte >= FIRST_PRINTABLE as u8 && byte <= LAST_PRINTABLE as u8) {
+            if !(byte >= FIRST_PRINTABLE && byte <= LAST_PRINTABLE) {
                 return Err(ParseAlphabetError::UnprintableByte(byte));
             }
 
-            let dup_idx = (byte - FIRST_PRINTABLE) as usize;
+            // Symbols differing only in case are duplicates for case insensitive alphabets
+            let folded = if self.case_insensitive { byte.to_ascii_lowercase() } else { byte };
+            let dup_idx = (folded - FIRST_PRINTABLE) as usize;
             if dups[dup_idx] {
                 return Err(ParseAlphabetError::DuplicatedByte(byte));
             }
 
             dups[dup_idx] = true;
-            index = index + 1;
+            index += 1;
         }
 
-        Ok(Self::from_str_unsafe(alphabet, encode_order))
+        // Aliases must not clash with symbols or each other and must refer to a symbol
+        let symbols = dups;
+        index = 0;
+        while index < self.aliases.len() {
+            let (alias, sym) = self.aliases[index];
+            if !(alias >= FIRST_PRINTABLE && alias <= LAST_PRINTABLE) {
+                return Err(ParseAlphabetError::UnprintableByte(alias));
+            }
+
+            let folded = if self.case_insensitive { alias.to_ascii_lowercase() } else { alias };
+            let dup_idx = (folded - FIRST_PRINTABLE) as usize;
+            if dups[dup_idx] {
+                return Err(ParseAlphabetError::DuplicatedByte(alias));
+            }
+
+            let folded = if self.case_insensitive { sym.to_ascii_lowercase() } else { sym };
+            let known = folded >= FIRST_PRINTABLE && folded <= LAST_PRINTABLE
+                && symbols[(folded - FIRST_PRINTABLE) as usize];
+            if !known {
+                return Err(ParseAlphabetError::UnknownAliasTarget(sym));
+            }
+
+            dups[dup_idx] = true;
+            index += 1;
+        }
+
+        // Padding must be distinguishable from anything decoded as a symbol
+        if let Some(pad) = self.padding {
+            if !(pad >= FIRST_PRINTABLE && pad <= LAST_PRINTABLE) {
+                return Err(ParseAlphabetError::UnprintableByte(pad));
+            }
+
+            let folded = if self.case_insensitive { pad.to_ascii_lowercase() } else { pad };
+            if dups[(folded - FIRST_PRINTABLE) as usize] {
+                return Err(ParseAlphabetError::PaddingCollision(pad));
+            }
+        }
+
+        Ok(Alphabet::from_parts_unsafe(
+            self.symbols,
+            self.bit_order,
+            self.octet_order,
+            self.case_insensitive,
+            self.aliases,
+            self.padding,
+        ))
     }
+}
 
-    pub const fn from_str(alphabet: &str) -> Result<Self, ParseAlphabetError> {
-        Self::from_str_order(alphabet, EncodeOrder::OrderNormal)
+impl Default for AlphabetBuilder<'_> {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
+/// Potential alphabet validation errors, new variants may be added in minor releases
 #[derive(Debug, Eq, PartialEq)]
+#[non_exhaustive]
 pub enum ParseAlphabetError {
     /// Alphabets must be 64 ASCII bytes
     InvalidLength,
@@ -80,15 +507,29 @@ pub enum ParseAlphabetError {
     DuplicatedByte(u8),
     /// All bytes must be printable (in the range `[32, 126]`).
     UnprintableByte(u8),
+    /// Aliases must refer to one of the symbols
+    UnknownAliasTarget(u8),
+    /// The padding character must not be a symbol or an alias
+    PaddingCollision(u8),
+    /// The group separator must not be a symbol or an alias
+    SeparatorCollision(u8),
+    /// The byte is not allowed by the requested character class
+    DisallowedByte(u8),
+    /// Alphabet specifications must start with a known order and `:`
+    InvalidSpec,
 }
 
-#[cfg(any(feature = "std", test))]
 impl fmt::Display for ParseAlphabetError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             ParseAlphabetError::InvalidLength => write!(f, "Invalid length - must be {} bytes", ALPHABET_SIZE),
             ParseAlphabetError::DuplicatedByte(b) => write!(f, "Duplicated byte: {:#04x}", b),
             ParseAlphabetError::UnprintableByte(b) => write!(f, "Unprintable byte: {:#04x}", b),
+            ParseAlphabetError::UnknownAliasTarget(b) => write!(f, "Alias of unknown symbol: {:#04x}", b),
+            ParseAlphabetError::PaddingCollision(b) => write!(f, "Padding is a symbol: {:#04x}", b),
+            ParseAlphabetError::SeparatorCollision(b) => write!(f, "Separator is a symbol: {:#04x}", b),
+            ParseAlphabetError::DisallowedByte(b) => write!(f, "Disallowed byte: {:#04x}", b),
+            ParseAlphabetError::InvalidSpec => write!(f, "Invalid specification - must be normal: or inversed:"),
         }
     }
 }
@@ -100,21 +541,119 @@ impl error::Error for ParseAlphabetError {}
 /// http://philzimmermann.com/docs/human-oriented-base-32-encoding.txt
 pub const ZBASE32: Alphabet = Alphabet::from_str_unsafe(
     "ybndrfg8ejkmcpqxot1uwisza345h769",
-    EncodeOrder::OrderInversed,
+    BitOrder::LsbFirst,
+);
+
+/// ZBase32 alphabet with octets encoded in the order described by the specification,
+/// as opposed to the Rspamd compatible [ZBASE32] that packs bits in reversed order.
+/// Use it to interoperate with other z-base-32 implementations.
+pub const ZBASE32_CANONICAL: Alphabet = Alphabet::from_str_unsafe(
+    "ybndrfg8ejkmcpqxot1uwisza345h769",
+    BitOrder::MsbFirst,
 );
 
+/// ZBase32 with bits packed most significant first as in RFC 4648, the same alphabet as
+/// [ZBASE32_CANONICAL] under a name that states the difference from [ZBASE32].
+///
+/// Choose [ZBASE32] to exchange data with Rspamd, e.g. `rspamd_util.encode_base32` and
+/// `decode_base32` in Lua or hashes stored by its modules, and this one for implementations
+/// following the z-base-32 specification, such as Go's `tv42/zbase32`
+pub const ZBASE32_RFC_ORDER: Alphabet = ZBASE32_CANONICAL;
+
 /// Bech32 alphabet used for bitcoin
 pub const BECH32: Alphabet = Alphabet::from_str_unsafe(
    "qpzry9x8gf2tvdw0s3jn54khce6mua7l",
-   EncodeOrder::OrderNormal,
+   BitOrder::MsbFirst,
 );
 
 /// RFC 4648 base32
 pub const RFC: Alphabet = Alphabet::from_str_unsafe(
     "ABCDEFGHIJKLMNOPQRSTUVWXYZ234567",
-    EncodeOrder::OrderNormal,
+    BitOrder::MsbFirst,
+);
+
+/// RFC 4648 base32 with lower case symbols, as expected by some DNS tooling and token formats
+pub const RFC_LOWER: Alphabet = Alphabet::from_str_unsafe(
+    "abcdefghijklmnopqrstuvwxyz234567",
+    BitOrder::MsbFirst,
+);
+
+/// RFC 4648 base32 restricted to DNS label characters: lower case symbols,
+/// decoding is case insensitive as DNS names are
+pub const DNS32: Alphabet = match Alphabet::from_str_dns_safe("abcdefghijklmnopqrstuvwxyz234567") {
+    Ok(alphabet) => alphabet,
+    Err(_) => panic!("DNS32 alphabet is not DNS safe"),
+};
+
+/// RFC 4648 base32 with extended hex alphabet, preserves sort order of the input
+/// https://datatracker.ietf.org/doc/html/rfc4648#section-7
+pub const BASE32HEX: Alphabet = Alphabet::from_str_unsafe(
+    "0123456789ABCDEFGHIJKLMNOPQRSTUV",
+    BitOrder::MsbFirst,
 );
 
+/// Crockford base32
+/// https://www.crockford.com/base32.html
+///
+/// Decoding is case insensitive, `O` is decoded as `0` and both `I` and `L` as `1`
+pub const CROCKFORD: Alphabet = Alphabet::from_parts_unsafe(
+    "0123456789ABCDEFGHJKMNPQRSTVWXYZ",
+    BitOrder::MsbFirst,
+    OctetOrder::Natural,
+    true,
+    &[(b'O', b'0'), (b'I', b'1'), (b'L', b'1')],
+    None,
+);
+
+/// Word-safe base32, meant for reading aloud and transcription: it has no vowels,
+/// so encoded strings never spell words, and avoids symbols that sound alike
+/// https://en.wikipedia.org/wiki/Base32#Word-safe_alphabet
+///
+/// Both cases are used as distinct symbols, so decoding is case sensitive
+pub const WORDSAFE: Alphabet = Alphabet::from_str_unsafe(
+    "23456789CFGHJMPQRVWXcfghjmpqrvwx",
+    BitOrder::MsbFirst,
+);
+
+/// Geohash base32, only the symbol mapping: coordinates are not interpreted
+/// https://en.wikipedia.org/wiki/Geohash
+pub const GEOHASH: Alphabet = Alphabet::from_str_unsafe(
+    "0123456789bcdefghjkmnpqrstuvwxyz",
+    BitOrder::MsbFirst,
+);
+
+/// Alphabets recognised by [detect_alphabet], one per distinct symbol set
+const DETECTABLE: [&Alphabet; 8] = [&ZBASE32, &RFC, &RFC_LOWER, &BECH32, &BASE32HEX, &CROCKFORD, &WORDSAFE, &GEOHASH];
+
+/// Returns the built-in alphabet whose symbols include every character of `sample`,
+/// or `None` if no alphabet or more than one does.
+///
+/// Only the symbol set is inspected, so alphabets that differ in packing order or case
+/// folding cannot be told apart: [ZBASE32] is returned rather than [ZBASE32_CANONICAL]
+/// and [RFC_LOWER] rather than [DNS32]. Short samples are often ambiguous, e.g. most
+/// z-base-32 strings are also valid lower case RFC 4648.
+///
+/// # Example
+///
+/// ```rust
+/// use rspamd_base32::alphabet::{detect_alphabet, RFC};
+///
+/// assert_eq!(Some(&RFC), detect_alphabet("JBSWY3DPEBLW64TMMQ"));
+/// assert_eq!(None, detect_alphabet("em3ags7p"));
+/// ```
+pub fn detect_alphabet(sample: &str) -> Option<&'static Alphabet> {
+    let mut found = None;
+    for alphabet in DETECTABLE {
+        if sample.bytes().all(|c| alphabet.encode_symbols.contains(&c)) {
+            if found.is_some() {
+                return None;
+            }
+            found = Some(alphabet);
+        }
+    }
+    found
+}
+
 #[cfg(test)]
 mod tests {
     use crate::alphabet::*;
@@ -167,6 +706,98 @@ mod tests {
         );
     }
 
+    #[test]
+    fn detects_unprintable_last() {
+        assert_eq!(
+            ParseAlphabetError::UnprintableByte(0x7f),
+            Alphabet::from_str("ABCDEFGHIJKLMNOPQRSTUVWXYZ23456\x7f")
+                .unwrap_err()
+        );
+        assert!(Alphabet::from_str("ABCDEFGHIJKLMNOPQRSTUVWXYZ23456~").is_ok());
+    }
+
+    #[test]
+    fn builder_same_as_from_str() {
+        assert_eq!(
+            ZBASE32,
+            AlphabetBuilder::new()
+                .symbols("ybndrfg8ejkmcpqxot1uwisza345h769")
+                .bit_order(BitOrder::LsbFirst)
+                .build()
+                .unwrap()
+        );
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn legacy_encode_order() {
+        for (order, alphabet) in [(EncodeOrder::OrderInversed, &ZBASE32), (EncodeOrder::OrderNormal, &RFC)] {
+            let symbols = core::str::from_utf8(alphabet.symbols()).unwrap();
+            assert_eq!(alphabet, &Alphabet::from_str_order(symbols, order.clone()).unwrap());
+            assert_eq!(
+                alphabet,
+                &AlphabetBuilder::new().symbols(symbols).encode_order(order.clone()).build().unwrap()
+            );
+            assert_eq!(&order, alphabet.encode_order());
+        }
+    }
+
+    #[test]
+    fn builder_case_insensitive() {
+        let alphabet = AlphabetBuilder::new()
+            .symbols("abcdefghijklmnopqrstuvwxyz234567")
+            .case_insensitive(true)
+            .build()
+            .unwrap();
+        for (idx, sym) in alphabet.encode_symbols.iter().enumerate() {
+            assert_eq!(idx as u8, alphabet.decode_bytes[*sym as usize]);
+            assert_eq!(idx as u8, alphabet.decode_bytes[sym.to_ascii_uppercase() as usize]);
+        }
+        assert_eq!(
+            ParseAlphabetError::DuplicatedByte(b'A'),
+            AlphabetBuilder::new()
+                .symbols("abcdefghijklmnopqrstuvwxyz23456A")
+                .case_insensitive(true)
+                .build()
+                .unwrap_err()
+        );
+    }
+
+    #[test]
+    fn builder_aliases() {
+        let alphabet = AlphabetBuilder::new()
+            .symbols("0123456789ABCDEFGHJKMNPQRSTVWXYZ")
+            .case_insensitive(true)
+            .aliases(&[(b'O', b'0'), (b'I', b'1'), (b'L', b'1')])
+            .build()
+            .unwrap();
+        assert_eq!(CROCKFORD, alphabet);
+        assert_eq!(
+            ParseAlphabetError::DuplicatedByte(b'A'),
+            AlphabetBuilder::new()
+                .symbols("0123456789ABCDEFGHJKMNPQRSTVWXYZ")
+                .aliases(&[(b'A', b'0')])
+                .build()
+                .unwrap_err()
+        );
+        assert_eq!(
+            ParseAlphabetError::UnknownAliasTarget(b'U'),
+            AlphabetBuilder::new()
+                .symbols("0123456789ABCDEFGHJKMNPQRSTVWXYZ")
+                .aliases(&[(b'O', b'U')])
+                .build()
+                .unwrap_err()
+        );
+    }
+
+    #[test]
+    fn builder_requires_symbols() {
+        assert_eq!(
+            ParseAlphabetError::InvalidLength,
+            AlphabetBuilder::new().build().unwrap_err()
+        );
+    }
+
     #[test]
     fn same_as_unchecked() {
         assert_eq!(
@@ -175,4 +806,241 @@ mod tests {
                 .unwrap()
         )
     }
-}
\ No newline at end of file
+
+    #[test]
+    #[allow(deprecated)]
+    fn shipped_same_as_constructed() {
+        assert_eq!(
+            ZBASE32,
+            Alphabet::from_str_order("ybndrfg8ejkmcpqxot1uwisza345h769", EncodeOrder::OrderInversed)
+                .unwrap()
+        );
+        assert_eq!(
+            ZBASE32_CANONICAL,
+            Alphabet::from_str_order("ybndrfg8ejkmcpqxot1uwisza345h769", EncodeOrder::OrderNormal)
+                .unwrap()
+        );
+        assert_eq!(
+            BECH32,
+            Alphabet::from_str_order("qpzry9x8gf2tvdw0s3jn54khce6mua7l", EncodeOrder::OrderNormal)
+                .unwrap()
+        );
+        assert_ne!(ZBASE32, ZBASE32_CANONICAL);
+        assert_eq!(
+            ZBASE32,
+            AlphabetBuilder::new()
+                .symbols("ybndrfg8ejkmcpqxot1uwisza345h769")
+                .bit_order(BitOrder::LsbFirst)
+                .build()
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn bits_per_symbol() {
+        assert_eq!(5, RFC.bits_per_symbol());
+        assert_eq!(ALPHABET_SIZE, 1 << ZBASE32.bits_per_symbol());
+        for len in 0..20_usize {
+            let bits = len * 8;
+            let symbols = bits.div_ceil(RFC.bits_per_symbol() as usize);
+            assert_eq!(Some(symbols), crate::encode::encoded_len(len));
+        }
+    }
+
+    #[test]
+    fn invert() {
+        assert_eq!(ZBASE32_RFC_ORDER, ZBASE32.invert());
+        assert_eq!(ZBASE32, ZBASE32.invert().invert());
+        assert_eq!(BitOrder::LsbFirst, RFC.invert().bit_order());
+
+        let spec = ZBASE32.invert();
+        let encoded = crate::encode::encode_alphabet("hello world", &spec);
+        assert_eq!("pb1sa5dxrb5s6hucco", encoded);
+        assert_eq!(b"hello world", &crate::decode::decode_alphabet(&encoded, &spec).unwrap()[..]);
+    }
+
+    #[test]
+    fn accessors() {
+        assert_eq!(b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567", RFC.symbols());
+        assert_eq!(b"ybndrfg8ejkmcpqxot1uwisza345h769", ZBASE32.symbols());
+        assert_eq!(BitOrder::MsbFirst, RFC.bit_order());
+        assert_eq!(BitOrder::LsbFirst, ZBASE32.bit_order());
+        assert_eq!(OctetOrder::Natural, ZBASE32.octet_order());
+        assert!(RFC.contains(b'A'));
+        assert!(!RFC.contains(b'a'));
+        assert!(!RFC.contains(b'1'));
+        assert!(ZBASE32.contains(b'1'));
+        assert!(!ZBASE32.contains(b'l'));
+        assert!(CROCKFORD.contains(b'l'));
+        assert!(ZBASE32.is_same_symbols(&ZBASE32_CANONICAL));
+        assert!(!RFC.is_same_symbols(&RFC_LOWER));
+    }
+
+    #[test]
+    fn decode_table_case_insensitive() {
+        let symbols = b"0123456789abcdefghjkmnpqrstvwxyz";
+        let table = compile_decode_table(symbols, true, &[(b'o', b'0'), (b'L', b'1')]);
+        for (idx, sym) in symbols.iter().enumerate() {
+            assert_eq!(idx as u8, table[sym.to_ascii_lowercase() as usize]);
+            assert_eq!(idx as u8, table[sym.to_ascii_uppercase() as usize]);
+        }
+        for (alias, value) in [(b'o', 0), (b'O', 0), (b'l', 1), (b'L', 1)] {
+            assert_eq!(value, table[alias as usize]);
+        }
+        assert_eq!(0xff, table[b'u' as usize]);
+        assert_eq!(0xff, table[b'U' as usize]);
+
+        let sensitive = compile_decode_table(symbols, false, &[]);
+        assert_eq!(10, sensitive[b'a' as usize]);
+        assert_eq!(0xff, sensitive[b'A' as usize]);
+
+        let alphabet = AlphabetBuilder::new()
+            .symbols("0123456789abcdefghjkmnpqrstvwxyz")
+            .case_insensitive(true)
+            .aliases(&[(b'o', b'0'), (b'L', b'1')])
+            .build()
+            .unwrap();
+        assert_eq!(table, alphabet.decode_bytes);
+    }
+
+    #[test]
+    fn builder_padding_collision() {
+        let symbols = "ABCDEFGHIJKLMNOPQRSTUVWXYZ23456=";
+        assert!(AlphabetBuilder::new().symbols(symbols).build().is_ok());
+        assert_eq!(
+            Err(ParseAlphabetError::PaddingCollision(b'=')),
+            AlphabetBuilder::new().symbols(symbols).padding(Some(b'=')).build()
+        );
+        assert_eq!(
+            Err(ParseAlphabetError::PaddingCollision(b'a')),
+            AlphabetBuilder::new().symbols(symbols).case_insensitive(true).padding(Some(b'a')).build()
+        );
+        assert_eq!(
+            Err(ParseAlphabetError::PaddingCollision(b'0')),
+            AlphabetBuilder::new()
+                .symbols(symbols)
+                .aliases(&[(b'0', b'O')])
+                .padding(Some(b'0'))
+                .build()
+        );
+        assert!(AlphabetBuilder::new().symbols(symbols).padding(Some(b'#')).build().is_ok());
+    }
+
+    #[test]
+    fn dns_safe() {
+        let alphabet = Alphabet::from_str_dns_safe("0123456789abcdefghijklmnopqrstuv").unwrap();
+        assert!(alphabet.contains(b'V'));
+        assert_eq!(RFC_LOWER.symbols(), DNS32.symbols());
+        assert!(DNS32.contains(b'A'));
+        assert_eq!(
+            Err(ParseAlphabetError::DisallowedByte(b'_')),
+            Alphabet::from_str_dns_safe("abcdefghijklmnopqrstuvwxyz23456_")
+        );
+        assert_eq!(
+            Err(ParseAlphabetError::DisallowedByte(b'A')),
+            Alphabet::from_str_dns_safe("Abcdefghijklmnopqrstuvwxyz234567")
+        );
+        assert_eq!(
+            Err(ParseAlphabetError::InvalidLength),
+            Alphabet::from_str_dns_safe("abc")
+        );
+    }
+
+    #[test]
+    fn parse_traits() {
+        let symbols = "ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+        assert_eq!(Ok(RFC), Alphabet::try_from(symbols));
+        assert_eq!(Ok(RFC), symbols.parse::<Alphabet>());
+        assert_eq!(Err(ParseAlphabetError::DuplicatedByte(b'A')), Alphabet::try_from("AACDEFGHIJKLMNOPQRSTUVWXYZ234567"));
+        assert_eq!(Err(ParseAlphabetError::InvalidLength), "ABC".parse::<Alphabet>());
+    }
+
+    #[test]
+    fn default_is_zbase() {
+        #[derive(Default)]
+        struct Codec {
+            alphabet: Alphabet,
+        }
+
+        assert_eq!(ZBASE32, Alphabet::default());
+        assert_eq!(ZBASE32, Codec::default().alphabet);
+    }
+
+    #[test]
+    fn hash_and_ord() {
+        use std::collections::{BTreeSet, HashSet};
+
+        let set: HashSet<Alphabet> = [RFC, ZBASE32, RFC].into_iter().collect();
+        assert_eq!(2, set.len());
+        assert!(set.contains(&RFC));
+        assert!(set.contains(&ZBASE32));
+        assert!(!set.contains(&ZBASE32_CANONICAL));
+
+        assert!(RFC < ZBASE32);
+        assert!(ZBASE32 < ZBASE32_CANONICAL);
+        assert_eq!(core::cmp::Ordering::Equal, RFC.cmp(&RFC.clone()));
+        // Case insensitivity is not hashed but still distinguishes alphabets
+        let folded = AlphabetBuilder::new()
+            .symbols("ABCDEFGHIJKLMNOPQRSTUVWXYZ234567")
+            .case_insensitive(true)
+            .build()
+            .unwrap();
+        assert_ne!(core::cmp::Ordering::Equal, RFC.cmp(&folded));
+        assert_eq!(2, [RFC, folded].into_iter().collect::<BTreeSet<_>>().len());
+    }
+
+    #[test]
+    fn detect() {
+        for alphabet in DETECTABLE {
+            let symbols = core::str::from_utf8(alphabet.symbols()).unwrap();
+            assert_eq!(Some(alphabet), detect_alphabet(symbols));
+        }
+        assert_eq!(Some(&ZBASE32), detect_alphabet("em3ags7py376g3tprd1"));
+        assert_eq!(Some(&RFC), detect_alphabet("HELLOWORLD"));
+        assert_eq!(Some(&CROCKFORD), detect_alphabet("D1JPRV3F41VPYWKCCG"));
+        // Valid z-base-32, lower case RFC 4648 and Bech32
+        assert_eq!(None, detect_alphabet("em3ags7py376g3tprd"));
+        assert_eq!(None, detect_alphabet("em3a-gs7p"));
+        assert_eq!(None, detect_alphabet(""));
+    }
+
+    #[test]
+    fn display_and_debug() {
+        assert!(format!("{}", RFC).contains("ABCDEFGHIJKLMNOPQRSTUVWXYZ234567"));
+        assert_eq!(
+            "Alphabet(\"ybndrfg8ejkmcpqxot1uwisza345h769\", LsbFirst, Natural)",
+            ZBASE32.to_string()
+        );
+        assert_eq!(
+            "Alphabet { symbols: \"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567\", bit_order: MsbFirst, \
+             octet_order: Natural, padding: None, .. }",
+            format!("{:?}", RFC)
+        );
+    }
+
+    #[test]
+    fn from_spec() {
+        assert_eq!(Ok(RFC), Alphabet::from_spec("normal:ABCDEFGHIJKLMNOPQRSTUVWXYZ234567"));
+        assert_eq!(Ok(ZBASE32), Alphabet::from_spec("inversed:ybndrfg8ejkmcpqxot1uwisza345h769"));
+        // Symbols may include the separator
+        assert!(Alphabet::from_spec("normal:ABCDEFGHIJKLMNOPQRSTUVWXYZ23456:").is_ok());
+
+        for spec in ["ABCDEFGHIJKLMNOPQRSTUVWXYZ234567", "reversed:ABCDEFGHIJKLMNOPQRSTUVWXYZ234567", ":"] {
+            assert_eq!(Err(ParseAlphabetError::InvalidSpec), Alphabet::from_spec(spec), "{}", spec);
+        }
+        assert_eq!(Err(ParseAlphabetError::InvalidLength), Alphabet::from_spec("normal:ABC"));
+        assert_eq!(
+            Err(ParseAlphabetError::DuplicatedByte(b'A')),
+            Alphabet::from_spec("normal:AACDEFGHIJKLMNOPQRSTUVWXYZ234567")
+        );
+    }
+
+    #[test]
+    fn has_padding() {
+        let symbols = "ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+        assert!(AlphabetBuilder::new().symbols(symbols).padding(Some(b'=')).build().unwrap().has_padding());
+        assert!(!AlphabetBuilder::new().symbols(symbols).build().unwrap().has_padding());
+        assert!(!RFC.has_padding());
+        assert!(!ZBASE32.has_padding());
+    }
+}
@@ -0,0 +1,280 @@
+//! Bech32 strings as defined in [BIP 173](https://github.com/bitcoin/bips/blob/master/bip-0173.mediawiki):
+//! human-readable part, separator `1`, data encoded with [BECH32] and a 6-symbol BCH checksum
+use crate::alphabet::BECH32;
+use crate::decode::{decode_alphabet_strict, DecodeError};
+use crate::encode::encode_alphabet;
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+#[cfg(any(feature = "std", test))]
+use std::error;
+
+/// Separates human-readable part from the data
+const SEPARATOR: u8 = b'1';
+/// Number of checksum symbols
+const CHECKSUM_LEN: usize = 6;
+/// Maximum length of the whole Bech32 string
+const MAX_LEN: usize = 90;
+/// BCH code generator coefficients
+const GENERATOR: [u32; 5] = [0x3b6a57b2, 0x26508e6d, 0x1ea119fa, 0x3d4233dd, 0x2a1462b3];
+
+/// Potential Bech32 errors
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Bech32Error {
+    /// The human-readable part is empty or contains characters outside `[33, 126]`.
+    InvalidHrp,
+    /// The string contains no separator.
+    MissingSeparator,
+    /// The string contains both lower and upper case characters.
+    MixedCase,
+    /// An invalid character was found. The offset and offending byte are provided.
+    InvalidChar(usize, u8),
+    /// The string is longer than 90 characters or too short to hold a checksum. The length is provided.
+    InvalidLength(usize),
+    /// The checksum does not match.
+    InvalidChecksum,
+    /// The data part does not decode to whole octets.
+    InvalidData(DecodeError),
+}
+
+impl fmt::Display for Bech32Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Bech32Error::InvalidHrp => write!(f, "Invalid human-readable part"),
+            Bech32Error::MissingSeparator => write!(f, "Missing separator"),
+            Bech32Error::MixedCase => write!(f, "Mixed case"),
+            Bech32Error::InvalidChar(index, byte) => {
+                write!(f, "Invalid character {}, offset {}.", byte, index)
+            }
+            Bech32Error::InvalidLength(len) => write!(f, "Invalid length: {}", len),
+            Bech32Error::InvalidChecksum => write!(f, "Invalid checksum"),
+            Bech32Error::InvalidData(e) => write!(f, "Invalid data: {}", e),
+        }
+    }
+}
+
+#[cfg(any(feature = "std", test))]
+impl error::Error for Bech32Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Bech32Error::InvalidData(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+fn polymod<I: IntoIterator<Item = u8>>(values: I) -> u32 {
+    let mut chk = 1_u32;
+    for v in values {
+        let top = chk >> 25;
+        chk = ((chk & 0x1ffffff) << 5) ^ v as u32;
+        for (i, g) in GENERATOR.iter().enumerate() {
+            if (top >> i) & 1 == 1 {
+                chk ^= g;
+            }
+        }
+    }
+    chk
+}
+
+/// Expands human-readable part into values checksummed before the data
+fn hrp_expand(hrp: &[u8]) -> impl Iterator<Item = u8> + '_ {
+    hrp.iter()
+        .map(|c| c >> 5)
+        .chain(core::iter::once(0))
+        .chain(hrp.iter().map(|c| c & 0x1f))
+}
+
+/// Maps data symbols to their 5-bit values, symbols must be valid
+fn symbol_values(symbols: &[u8]) -> impl Iterator<Item = u8> + '_ {
+    symbols.iter().map(|c| BECH32.decode_bytes[*c as usize])
+}
+
+fn valid_hrp(hrp: &[u8]) -> bool {
+    !hrp.is_empty() && hrp.iter().all(|c| (33..=126).contains(c))
+}
+
+///Encode octets as a Bech32 string with the human-readable part `hrp`.
+///Returns a lower case `String`.
+///
+///# Example
+///
+///```rust
+///use rspamd_base32::bech32::{decode_bech32, encode_bech32};
+///
+///fn main() {
+///    let encoded = encode_bech32("test", b"hello").unwrap();
+///    assert_eq!(("test".to_owned(), b"hello".to_vec()), decode_bech32(&encoded).unwrap());
+///}
+///```
+pub fn encode_bech32(hrp: &str, data: &[u8]) -> Result<String, Bech32Error> {
+    if !valid_hrp(hrp.as_bytes()) {
+        return Err(Bech32Error::InvalidHrp);
+    }
+
+    let hrp = hrp.to_ascii_lowercase();
+    let symbols = encode_alphabet(data, &BECH32);
+    let len = hrp.len() + 1 + symbols.len() + CHECKSUM_LEN;
+    if len > MAX_LEN {
+        return Err(Bech32Error::InvalidLength(len));
+    }
+
+    let checksum = polymod(
+        hrp_expand(hrp.as_bytes())
+            .chain(symbol_values(symbols.as_bytes()))
+            .chain([0; CHECKSUM_LEN]),
+    ) ^ 1;
+
+    let mut out = String::with_capacity(len);
+    out.push_str(&hrp);
+    out.push(SEPARATOR as char);
+    out.push_str(&symbols);
+    for i in 0..CHECKSUM_LEN {
+        let value = (checksum >> (5 * (CHECKSUM_LEN - 1 - i))) & 0x1f;
+        out.push(BECH32.encode_symbols[value as usize] as char);
+    }
+
+    Ok(out)
+}
+
+///Decode a Bech32 string, verifying its checksum.
+///Returns the lower case human-readable part and decoded octets.
+pub fn decode_bech32(s: &str) -> Result<(String, Vec<u8>), Bech32Error> {
+    decode_bech32_impl(s, false)
+}
+
+///Decode a Bech32 string as [decode_bech32] does, accepting mixed case input that BIP 173
+///forbids, e.g. hand typed addresses. The checksum is verified on the lower case string.
+///
+///# Example
+///
+///```rust
+///use rspamd_base32::bech32::decode_bech32_lenient;
+///
+///fn main() {
+///    let (hrp, data) = decode_bech32_lenient("a12UEL5L").unwrap();
+///    assert_eq!(("a", &b""[..]), (hrp.as_str(), &data[..]));
+///}
+///```
+pub fn decode_bech32_lenient(s: &str) -> Result<(String, Vec<u8>), Bech32Error> {
+    decode_bech32_impl(s, true)
+}
+
+fn decode_bech32_impl(s: &str, mixed_case: bool) -> Result<(String, Vec<u8>), Bech32Error> {
+    let bytes = s.as_bytes();
+
+    if let Some(i) = bytes.iter().position(|c| !(33..=126).contains(c)) {
+        return Err(Bech32Error::InvalidChar(i, bytes[i]));
+    }
+    if !mixed_case && bytes.iter().any(u8::is_ascii_lowercase) && bytes.iter().any(u8::is_ascii_uppercase) {
+        return Err(Bech32Error::MixedCase);
+    }
+    if bytes.len() > MAX_LEN {
+        return Err(Bech32Error::InvalidLength(bytes.len()));
+    }
+
+    let lower = s.to_ascii_lowercase();
+    let bytes = lower.as_bytes();
+    let sep = bytes.iter().rposition(|&c| c == SEPARATOR).ok_or(Bech32Error::MissingSeparator)?;
+    let (hrp, symbols) = (&bytes[..sep], &bytes[sep + 1..]);
+
+    if !valid_hrp(hrp) {
+        return Err(Bech32Error::InvalidHrp);
+    }
+    if symbols.len() < CHECKSUM_LEN {
+        return Err(Bech32Error::InvalidLength(bytes.len()));
+    }
+    if let Some(i) = symbols.iter().position(|c| BECH32.decode_bytes[*c as usize] == 0xff) {
+        return Err(Bech32Error::InvalidChar(sep + 1 + i, symbols[i]));
+    }
+    if polymod(hrp_expand(hrp).chain(symbol_values(symbols))) != 1 {
+        return Err(Bech32Error::InvalidChecksum);
+    }
+
+    let data = decode_alphabet_strict(&symbols[..symbols.len() - CHECKSUM_LEN], &BECH32)
+        .map_err(Bech32Error::InvalidData)?;
+
+    Ok((String::from(&lower[..sep]), data))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bech32::*;
+
+    #[test]
+    fn bip173_valid() {
+        for s in [
+            "A12UEL5L",
+            "a12uel5l",
+            "an83characterlonghumanreadablepartthatcontainsthenumber1andtheexcludedcharactersbio1tt5tgs",
+            "abcdef1qpzry9x8gf2tvdw0s3jn54khce6mua7lmqqqxw",
+            "11qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqc8247j",
+            "split1checkupstagehandshakeupstreamerranterredcaperred2y9e3w",
+            "?1ezyfcl",
+        ] {
+            let (hrp, data) = decode_bech32(s).unwrap_or_else(|e| panic!("{}: {}", s, e));
+            assert_eq!(s.to_ascii_lowercase(), encode_bech32(&hrp, &data).unwrap());
+        }
+    }
+
+    #[test]
+    fn bip173_invalid() {
+        for (s, err) in [
+            ("\x201nwldj5", Bech32Error::InvalidChar(0, 0x20)),
+            ("\x7f1axkwrx", Bech32Error::InvalidChar(0, 0x7f)),
+            ("\u{80}1eym55h", Bech32Error::InvalidChar(0, 0xc2)),
+            (
+                "an84characterslonghumanreadablepartthatcontainsthenumber1andtheexcludedcharactersbio1569pvx",
+                Bech32Error::InvalidLength(91),
+            ),
+            ("pzry9x0s0muk", Bech32Error::MissingSeparator),
+            ("1pzry9x0s0muk", Bech32Error::InvalidHrp),
+            ("x1b4n0q5v", Bech32Error::InvalidChar(2, b'b')),
+            ("li1dgmt3", Bech32Error::InvalidLength(8)),
+            ("de1lg7wt\u{ff}", Bech32Error::InvalidChar(8, 0xc3)),
+            ("A1G7SGD8", Bech32Error::InvalidChecksum),
+            ("10a06t8", Bech32Error::InvalidHrp),
+            ("1qzzfhee", Bech32Error::InvalidHrp),
+            ("a12UEL5L", Bech32Error::MixedCase),
+        ] {
+            assert_eq!(Err(err), decode_bech32(s), "{}", s);
+        }
+    }
+
+    #[test]
+    fn lenient_case() {
+        let lower = encode_bech32("bc", b"hello").unwrap();
+        let upper = lower.to_ascii_uppercase();
+        let mixed: String = lower
+            .chars()
+            .enumerate()
+            .map(|(i, c)| if i % 2 == 0 { c.to_ascii_uppercase() } else { c })
+            .collect();
+        let expected = ("bc".to_owned(), b"hello".to_vec());
+
+        for s in [&lower, &upper, &mixed] {
+            assert_eq!(Ok(expected.clone()), decode_bech32_lenient(s), "{}", s);
+        }
+        assert_eq!(Ok(expected.clone()), decode_bech32(&upper));
+        assert_eq!(Err(Bech32Error::MixedCase), decode_bech32(&mixed));
+
+        // The checksum is still verified after lowercasing
+        let mut corrupted = mixed.into_bytes();
+        let last = corrupted.len() - 1;
+        corrupted[last] = if corrupted[last].eq_ignore_ascii_case(&b'q') { b'p' } else { b'q' };
+        let corrupted = String::from_utf8(corrupted).unwrap();
+        assert_eq!(Err(Bech32Error::InvalidChecksum), decode_bech32_lenient(&corrupted));
+    }
+
+    #[test]
+    fn roundtrip() {
+        let data: Vec<u8> = (0..40).collect();
+        for len in 0..data.len() {
+            let encoded = encode_bech32("bc", &data[..len]).unwrap();
+            assert_eq!(("bc".into(), data[..len].to_vec()), decode_bech32(&encoded).unwrap());
+        }
+        assert_eq!(Err(Bech32Error::InvalidHrp), encode_bech32("", b"a"));
+        assert_eq!(Err(Bech32Error::InvalidLength(91)), encode_bech32("bc", &[0; 51]));
+    }
+}
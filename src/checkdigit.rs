@@ -0,0 +1,157 @@
+//! Single-symbol check digit for human-transcribed base32 strings
+//!
+//! Uses the [Damm algorithm](https://en.wikipedia.org/wiki/Damm_algorithm) over the
+//! quasigroup `x * y = 2x + y` in GF(32), which detects every single-symbol error and
+//! every transposition of adjacent symbols before the check symbol.
+use crate::alphabet::Alphabet;
+
+use alloc::string::String;
+use core::fmt;
+#[cfg(any(feature = "std", test))]
+use std::error;
+
+/// Potential check digit errors
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CheckError {
+    /// The input contains no check symbol.
+    Empty,
+    /// An invalid byte was found. The offset and offending byte are provided.
+    InvalidByte(usize, u8),
+    /// The check symbol does not match.
+    Mismatch,
+}
+
+impl fmt::Display for CheckError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CheckError::Empty => write!(f, "Missing check symbol"),
+            CheckError::InvalidByte(index, byte) => {
+                write!(f, "Invalid byte {}, offset {}.", byte, index)
+            }
+            CheckError::Mismatch => write!(f, "Check symbol mismatch"),
+        }
+    }
+}
+
+#[cfg(any(feature = "std", test))]
+impl error::Error for CheckError {}
+
+/// Multiplies a 5-bit value by `x` modulo `x^5 + x^2 + 1`
+const fn mul2(value: u8) -> u8 {
+    let shifted = value << 1;
+    if shifted & 0x20 != 0 {
+        shifted ^ 0x25
+    } else {
+        shifted
+    }
+}
+
+/// Runs the Damm state over `symbols`, returns the offset of the first invalid byte on error
+fn interim(symbols: &[u8], alphabet: &Alphabet) -> Result<u8, usize> {
+    let mut state = 0_u8;
+    for (i, &c) in symbols.iter().enumerate() {
+        let value = alphabet.decode_bytes[c as usize];
+        if value == 0xff {
+            return Err(i);
+        }
+        state = mul2(state) ^ value;
+    }
+    Ok(state)
+}
+
+///Append a check symbol to base32 `encoded` with the specified [Alphabet].
+///Returns a new `String` one symbol longer than the input.
+///
+///# Panics
+///
+///Panics if `encoded` contains bytes that are not symbols of the alphabet.
+///
+///# Example
+///
+///```rust
+///use rspamd_base32::alphabet::ZBASE32;
+///use rspamd_base32::checkdigit::{append_check, verify_check};
+///
+///fn main() {
+///    let checked = append_check("em3ags7p", &ZBASE32);
+///    assert_eq!(Ok("em3ags7p"), verify_check(&checked, &ZBASE32));
+///}
+///```
+pub fn append_check(encoded: &str, alphabet: &Alphabet) -> String {
+    let state = interim(encoded.as_bytes(), alphabet)
+        .unwrap_or_else(|i| panic!("invalid symbol at offset {}", i));
+    // The check symbol brings the state back to zero: 2 * state + check = 0
+    let check = mul2(state);
+
+    let mut out = String::with_capacity(encoded.len() + 1);
+    out.push_str(encoded);
+    out.push(alphabet.encode_symbols[check as usize] as char);
+    out
+}
+
+///Verify the trailing check symbol of `encoded_with_check` with the specified [Alphabet].
+///Returns the input without the check symbol.
+pub fn verify_check<'a>(encoded_with_check: &'a str, alphabet: &Alphabet) -> Result<&'a str, CheckError> {
+    if encoded_with_check.is_empty() {
+        return Err(CheckError::Empty);
+    }
+
+    let bytes = encoded_with_check.as_bytes();
+    match interim(bytes, alphabet) {
+        Err(i) => Err(CheckError::InvalidByte(i, bytes[i])),
+        Ok(0) => Ok(&encoded_with_check[..bytes.len() - 1]),
+        Ok(_) => Err(CheckError::Mismatch),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::alphabet::*;
+    use crate::checkdigit::*;
+    use crate::encode::encode_alphabet;
+
+    #[test]
+    fn detects_flipped_symbol() {
+        for alphabet in [&ZBASE32, &RFC] {
+            let encoded = encode_alphabet("hello world", alphabet);
+            let checked = append_check(&encoded, alphabet);
+            assert_eq!(encoded.len() + 1, checked.len());
+            assert_eq!(Ok(encoded.as_str()), verify_check(&checked, alphabet));
+
+            for pos in 0..checked.len() {
+                for &sym in alphabet.symbols() {
+                    let mut flipped = checked.clone().into_bytes();
+                    if flipped[pos] == sym {
+                        continue;
+                    }
+                    flipped[pos] = sym;
+                    let flipped = String::from_utf8(flipped).unwrap();
+                    assert_eq!(Err(CheckError::Mismatch), verify_check(&flipped, alphabet));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn detects_adjacent_transposition() {
+        let checked = append_check(&encode_alphabet("test123", &ZBASE32), &ZBASE32);
+        // Swaps of data symbols, the check symbol is last
+        for pos in 0..checked.len() - 2 {
+            let mut swapped = checked.clone().into_bytes();
+            if swapped[pos] == swapped[pos + 1] {
+                continue;
+            }
+            swapped.swap(pos, pos + 1);
+            let swapped = String::from_utf8(swapped).unwrap();
+            assert_eq!(Err(CheckError::Mismatch), verify_check(&swapped, &ZBASE32), "{}", pos);
+        }
+    }
+
+    #[test]
+    fn invalid_input() {
+        assert_eq!(Err(CheckError::Empty), verify_check("", &ZBASE32));
+        assert_eq!(Err(CheckError::InvalidByte(3, b'!')), verify_check("em3!gs7p", &ZBASE32));
+        // A single symbol carries the check only
+        assert_eq!(Ok(""), verify_check(&append_check("", &ZBASE32), &ZBASE32));
+    }
+}
@@ -1,27 +1,153 @@
 //! Base32 decoding routines
-use crate::alphabet::{Alphabet, ZBASE32, EncodeOrder};
-
+use crate::alphabet::{Alphabet, BitOrder, OctetOrder};
+use crate::encode::encode_iter;
 #[cfg(any(feature = "alloc", feature = "std", test))]
+use crate::alphabet::ZBASE32;
+
 use core::fmt;
+#[cfg(any(feature = "alloc", feature = "std", test))]
+use alloc::{string::String, vec, vec::Vec};
 #[cfg(any(feature = "std", test))]
 use std::error;
 
 /// Potential decoding errors
+///
+/// New variants may be added in minor releases, so matches outside of this crate
+/// need a wildcard arm:
+///
+/// ```rust
+/// use rspamd_base32::decode::DecodeError;
+///
+/// fn describe(e: &DecodeError) -> &'static str {
+///     match e {
+///         DecodeError::InvalidByte(..) => "bad symbol",
+///         DecodeError::InvalidLength { .. } => "bad length",
+///         _ => "other",
+///     }
+/// }
+///
+/// fn main() {
+///     let e = rspamd_base32::decode("y").unwrap_err();
+///     assert_eq!("bad length", describe(&e));
+/// }
+/// ```
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum DecodeError {
     /// An invalid byte was found in the input. The offset and offending byte are provided.
     InvalidByte(usize, u8),
-    /// The length of the input is invalid.
-    InvalidLength(usize),
+    /// The length of the input is invalid. The length and the number of trailing symbols
+    /// that cannot form whole octets are provided.
+    InvalidLength { len: usize, remainder: usize },
+    /// The output buffer is too small to hold the decoded data.
+    OutputTooSmall { needed: usize, got: usize },
+    /// The padding is malformed (interior or wrong amount of padding). The offset is provided.
+    InvalidPadding(usize),
+    /// The last symbol has unused bits set, so no encoder would produce this input.
+    /// The offset of that symbol is provided.
+    NonCanonical { index: usize },
+    /// The input decodes to a different number of octets than expected.
+    UnexpectedLength { expected: usize, got: usize },
+    /// The input decodes to more octets than allowed. Both lengths are provided.
+    TooLong { needed: usize, max: usize },
+    /// A padding character was found where no padding is accepted. The offset is provided.
+    /// Reported instead of [DecodeError::InvalidByte] by [decode_human],
+    /// [decode_alphabet_lenient] and [decode_alphabet_padded] only.
+    UnexpectedPadding(usize),
+    /// An invalid byte was found that looks like the symbol `interpreted_as` of the alphabet,
+    /// e.g. `0` for `O` in [crate::alphabet::RFC]. Reported by the same functions as
+    /// [DecodeError::UnexpectedPadding].
+    ConfusableByte { index: usize, byte: u8, interpreted_as: u8 },
+    /// The padding character is also a symbol of the alphabet, so padding cannot be told
+    /// apart from data. The padding character is provided.
+    PaddingCollision(u8),
+}
+
+impl DecodeError {
+    /// Moves offsets of offending bytes by `base`, for errors in a part of a larger input
+    pub(crate) fn offset_bytes(self, base: usize) -> Self {
+        match self {
+            DecodeError::InvalidByte(index, byte) => DecodeError::InvalidByte(base + index, byte),
+            DecodeError::UnexpectedPadding(index) => DecodeError::UnexpectedPadding(base + index),
+            DecodeError::ConfusableByte { index, byte, interpreted_as } => {
+                DecodeError::ConfusableByte { index: base + index, byte, interpreted_as }
+            }
+            DecodeError::NonCanonical { index } => DecodeError::NonCanonical { index: base + index },
+            e => e,
+        }
+    }
+}
+
+/// Groups of bytes that are easily mistaken for each other when transcribed
+const CONFUSABLES: [&[u8]; 5] = [b"0Oo", b"1IiLl", b"2Zz", b"5Ss", b"8B"];
+
+/// Classifies a byte rejected by `alphabet` for a more helpful error if `options` ask for it,
+/// the default API keeps reporting [DecodeError::InvalidByte]
+fn invalid_byte(index: usize, byte: u8, alphabet: &Alphabet, options: &DecodeOptions) -> DecodeError {
+    if !options.classify {
+        return DecodeError::InvalidByte(index, byte);
+    }
+    if byte == alphabet.padding.unwrap_or(PAD) {
+        return DecodeError::UnexpectedPadding(index);
+    }
+
+    let lookalike = CONFUSABLES
+        .iter()
+        .find(|group| group.contains(&byte))
+        .and_then(|group| group.iter().find(|&&c| alphabet.contains(c)));
+    match lookalike {
+        Some(&interpreted_as) => DecodeError::ConfusableByte { index, byte, interpreted_as },
+        None => DecodeError::InvalidByte(index, byte),
+    }
+}
+
+/// Padding policy for [decode_alphabet_padded]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Padding {
+    /// Input must be padded to a multiple of 8 symbols
+    Require,
+    /// Input may be either padded or unpadded
+    Accept,
+    /// Input must not contain padding
+    Forbid,
 }
 
+/// Padding character defined by RFC 4648
+pub const PAD: u8 = b'=';
+
 impl fmt::Display for DecodeError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
             DecodeError::InvalidByte(index, byte) => {
                 write!(f, "Invalid byte {}, offset {}.", byte, index)
             }
-            DecodeError::InvalidLength(sz) => write!(f, "Encoded text cannot have a 5-bit remainder: length = {}", sz),
+            DecodeError::InvalidLength { len, remainder } => write!(
+                f,
+                "Encoded text cannot have {} trailing symbols: length = {}",
+                remainder, len
+            ),
+            DecodeError::OutputTooSmall { needed, got } => {
+                write!(f, "Output buffer is too small: needed {}, got {}", needed, got)
+            }
+            DecodeError::InvalidPadding(index) => write!(f, "Invalid padding, offset {}.", index),
+            DecodeError::NonCanonical { index } => {
+                write!(f, "Non-canonical trailing bits, offset {}.", index)
+            }
+            DecodeError::UnexpectedLength { expected, got } => {
+                write!(f, "Unexpected decoded length: expected {}, got {}", expected, got)
+            }
+            DecodeError::TooLong { needed, max } => {
+                write!(f, "Decoded data is too long: needed {}, max {}", needed, max)
+            }
+            DecodeError::UnexpectedPadding(index) => write!(f, "Unexpected padding, offset {}.", index),
+            DecodeError::ConfusableByte { index, byte, interpreted_as } => write!(
+                f,
+                "Invalid byte {}, offset {}, did you mean '{}'?",
+                byte, index, interpreted_as as char
+            ),
+            DecodeError::PaddingCollision(pad) => {
+                write!(f, "Padding character '{}' is an alphabet symbol", pad as char)
+            }
         }
     }
 }
@@ -31,7 +157,15 @@ impl error::Error for DecodeError {
     fn description(&self) -> &str {
         match *self {
             DecodeError::InvalidByte(_, _) => "invalid byte",
-            DecodeError::InvalidLength(_) => "invalid length",
+            DecodeError::InvalidLength { .. } => "invalid length",
+            DecodeError::OutputTooSmall { .. } => "output too small",
+            DecodeError::InvalidPadding(_) => "invalid padding",
+            DecodeError::NonCanonical { .. } => "non-canonical encoding",
+            DecodeError::UnexpectedLength { .. } => "unexpected length",
+            DecodeError::TooLong { .. } => "too long",
+            DecodeError::UnexpectedPadding(_) => "unexpected padding",
+            DecodeError::ConfusableByte { .. } => "confusable byte",
+            DecodeError::PaddingCollision(_) => "padding collision",
         }
     }
 
@@ -40,6 +174,14 @@ impl error::Error for DecodeError {
     }
 }
 
+/// Maps to [std::io::ErrorKind::InvalidData], the original error is kept as the inner error
+#[cfg(any(feature = "std", test))]
+impl From<DecodeError> for std::io::Error {
+    fn from(e: DecodeError) -> Self {
+        std::io::Error::new(std::io::ErrorKind::InvalidData, e)
+    }
+}
+
 ///Decode base32 using the default alphabet
 ///Returns a `Result` containing a `Vec<u8>`.
 ///
@@ -84,12 +226,368 @@ pub fn decode_alphabet<T: AsRef<[u8]>>(
     let mut buffer = Vec::<u8>::with_capacity(
         decoded_len(input.as_ref().len()).expect("integer multiplication overflow"));
 
-    decode_alphabet_vec(input, &mut buffer, &alphabet).map(|_| buffer)
+    decode_alphabet_vec(input, &mut buffer, alphabet).map(|_| buffer)
+}
+
+///Decode from string reference as octets using the specified [Alphabet],
+///handling trailing padding according to the [Padding] policy.
+///The padding character is `=` unless the alphabet defines its own, and
+///[DecodeError::PaddingCollision] is returned if the alphabet accepts it as a symbol.
+///Returns a `Result` containing a `Vec<u8>`.
+///
+///# Example
+///
+///```rust
+///extern crate rspamd_base32;
+///use rspamd_base32::decode::Padding;
+///
+///fn main() {
+///    let bytes = rspamd_base32::decode_alphabet_padded(
+///        "ME======",
+///        &rspamd_base32::alphabet::RFC,
+///        Padding::Require,
+///    ).unwrap();
+///    assert_eq!(b"a", &bytes[..]);
+///}
+///```
+#[cfg(any(feature = "alloc", feature = "std", test))]
+pub fn decode_alphabet_padded<T: AsRef<[u8]>>(
+    input: T,
+    alphabet: &Alphabet,
+    padding: Padding,
+) -> Result<Vec<u8>, DecodeError> {
+    let pad = alphabet.padding.unwrap_or(PAD);
+    if alphabet.contains(pad) {
+        return Err(DecodeError::PaddingCollision(pad));
+    }
+
+    let data = strip_padding(input.as_ref(), padding, pad)?;
+    decode_with_options(data, alphabet, DecodeOptions { classify: true, ..Default::default() })
+}
+
+/// Validates trailing padding against the policy and returns the unpadded data
+#[cfg(any(feature = "alloc", feature = "std", test))]
+fn strip_padding(input: &[u8], padding: Padding, pad: u8) -> Result<&[u8], DecodeError> {
+    let data_len = input.iter().rposition(|&c| c != pad).map_or(0, |pos| pos + 1);
+    let data = &input[..data_len];
+    let pad_count = input.len() - data_len;
+
+    if let Some(pos) = data.iter().position(|&c| c == pad) {
+        return Err(DecodeError::InvalidPadding(pos));
+    }
+
+    let padded = input.len().is_multiple_of(8) && pad_count < 8;
+    let valid = match padding {
+        Padding::Forbid => pad_count == 0,
+        Padding::Require => padded,
+        Padding::Accept => pad_count == 0 || padded,
+    };
+
+    if !valid {
+        return Err(DecodeError::InvalidPadding(data_len));
+    }
+
+    Ok(data)
+}
+
+///Decode from string reference as octets using the specified [Alphabet],
+///rejecting non-canonical input whose last symbol has unused bits set.
+///Every byte string thus has exactly one accepted encoding.
+///
+///This also catches most inputs truncated inside the last group: the symbols left
+///carry bits of the dropped octet, which lenient decoding silently discards.
+///A truncation that happens to leave only zero unused bits cannot be told apart
+///from a valid shorter encoding.
+///Returns a `Result` containing a `Vec<u8>`.
+///
+///# Example
+///
+///```rust
+///extern crate rspamd_base32;
+///use rspamd_base32::decode::DecodeError;
+///
+///fn main() {
+///    let rfc = &rspamd_base32::alphabet::RFC;
+///    assert_eq!(b"a", &rspamd_base32::decode_alphabet_strict("ME", rfc).unwrap()[..]);
+///    assert_eq!(
+///        Err(DecodeError::NonCanonical { index: 1 }),
+///        rspamd_base32::decode_alphabet_strict("MF", rfc),
+///    );
+///}
+///```
+#[cfg(any(feature = "alloc", feature = "std", test))]
+pub fn decode_alphabet_strict<T: AsRef<[u8]>>(
+    input: T,
+    alphabet: &Alphabet,
+) -> Result<Vec<u8>, DecodeError> {
+    decode_with_options(input.as_ref(), alphabet, DecodeOptions { strict: true, ..Default::default() })
+}
+
+///Decode from string reference as octets using the specified [Alphabet],
+///refusing input that decodes to more than `max_output` octets, e.g. untrusted requests.
+///Returns [DecodeError::TooLong] before allocating anything if the limit is exceeded.
+///
+///# Example
+///
+///```rust
+///use rspamd_base32::alphabet::ZBASE32;
+///use rspamd_base32::decode::{decode_alphabet_bounded, DecodeError};
+///
+///fn main() {
+///    assert_eq!(Ok(b"hello".to_vec()), decode_alphabet_bounded("em3ags7p", &ZBASE32, 5));
+///    assert_eq!(
+///        Err(DecodeError::TooLong { needed: 5, max: 4 }),
+///        decode_alphabet_bounded("em3ags7p", &ZBASE32, 4)
+///    );
+///}
+///```
+#[cfg(any(feature = "alloc", feature = "std", test))]
+pub fn decode_alphabet_bounded<T: AsRef<[u8]>>(
+    input: T,
+    alphabet: &Alphabet,
+    max_output: usize,
+) -> Result<Vec<u8>, DecodeError> {
+    let input_bytes = input.as_ref();
+    if !is_valid_encoded_len(input_bytes.len()) {
+        return Err(invalid_length(input_bytes.len()));
+    }
+
+    let needed = exact_decoded_len(input_bytes.len());
+    if needed > max_output {
+        return Err(DecodeError::TooLong { needed, max: max_output });
+    }
+
+    decode_with_options(input_bytes, alphabet, DecodeOptions::default())
+}
+
+///Decode from string reference as octets using the specified [Alphabet],
+///ignoring ASCII whitespace (`' '`, `'\t'`, `'\r'`, `'\n'`) such as line wrapping.
+///Offsets in errors refer to the original input.
+///Returns a `Result` containing a `Vec<u8>`.
+///
+///# Example
+///
+///```rust
+///extern crate rspamd_base32;
+///
+///fn main() {
+///    let bytes = rspamd_base32::decode_alphabet_lenient(
+///        "NBSW\nY3DP\n",
+///        &rspamd_base32::alphabet::RFC,
+///    ).unwrap();
+///    assert_eq!(b"hello", &bytes[..]);
+///}
+///```
+#[cfg(any(feature = "alloc", feature = "std", test))]
+pub fn decode_alphabet_lenient<T: AsRef<[u8]>>(
+    input: T,
+    alphabet: &Alphabet,
+) -> Result<Vec<u8>, DecodeError> {
+    let options = DecodeOptions { skip_whitespace: true, classify: true, ..Default::default() };
+    decode_with_options(input.as_ref(), alphabet, options)
+}
+
+///Decode from string reference as octets using the specified [Alphabet],
+///ignoring the ASCII group separator `sep`, e.g. as produced by [crate::encode::encode_grouped].
+///A `sep` accepted by the alphabet is decoded as a symbol, the encoder rejects such separators.
+///Offsets in errors refer to the original input.
+///Returns a `Result` containing a `Vec<u8>`.
+///
+///# Example
+///
+///```rust
+///extern crate rspamd_base32;
+///
+///fn main() {
+///    let bytes = rspamd_base32::decode_alphabet_grouped(
+///        "em3a-gs7p",
+///        &rspamd_base32::alphabet::ZBASE32,
+///        b'-',
+///    ).unwrap();
+///    assert_eq!(b"hello", &bytes[..]);
+///}
+///```
+#[cfg(any(feature = "alloc", feature = "std", test))]
+pub fn decode_alphabet_grouped<T: AsRef<[u8]>>(
+    input: T,
+    alphabet: &Alphabet,
+    sep: u8,
+) -> Result<Vec<u8>, DecodeError> {
+    decode_with_options(input.as_ref(), alphabet, DecodeOptions { separator: Some(sep), ..Default::default() })
+}
+
+///Decode user-typed input as octets using the specified [Alphabet], ignoring ASCII
+///whitespace and hyphens and accepting symbols in either ASCII case.
+///Offsets in errors refer to the original input.
+///Returns a `Result` containing a `Vec<u8>`.
+///
+///Case folding only applies when the symbols of the alphabet are single-case: alphabets
+///such as [crate::alphabet::WORDSAFE] use both cases as distinct symbols and stay case sensitive.
+///
+///# Example
+///
+///```rust
+///extern crate rspamd_base32;
+///
+///fn main() {
+///    let bytes = rspamd_base32::decode_human("NB-sw y3dp", &rspamd_base32::alphabet::RFC).unwrap();
+///    assert_eq!(b"hello", &bytes[..]);
+///}
+///```
+#[cfg(any(feature = "alloc", feature = "std", test))]
+pub fn decode_human<T: AsRef<[u8]>>(input: T, alphabet: &Alphabet) -> Result<Vec<u8>, DecodeError> {
+    let options = DecodeOptions {
+        skip_whitespace: true,
+        separator: Some(b'-'),
+        fold_case: is_single_case(alphabet),
+        classify: true,
+        ..Default::default()
+    };
+    decode_with_options(input.as_ref(), alphabet, options)
+}
+
+///Decode input using the `from` [Alphabet] and encode the octets using the `to` [Alphabet],
+///e.g. to store RFC 4648 input as zbase32. Packing orders of the alphabets may differ.
+///Returns a `Result` containing a `String`.
+///
+///# Example
+///
+///```rust
+///use rspamd_base32::alphabet::{RFC, ZBASE32};
+///
+///fn main() {
+///    assert_eq!(Ok("em3ags7p".to_owned()), rspamd_base32::transcode("NBSWY3DP", &RFC, &ZBASE32));
+///}
+///```
+#[cfg(any(feature = "alloc", feature = "std", test))]
+pub fn transcode(input: &str, from: &Alphabet, to: &Alphabet) -> Result<String, DecodeError> {
+    let mut octets = Vec::new();
+    decode_alphabet_vec(input, &mut octets, from)?;
+    Ok(crate::encode::encode_alphabet(&octets, to))
+}
+
+/// Checks that no symbols differ from each other by ASCII case only,
+/// so that folding the case of the input is unambiguous
+#[cfg(any(feature = "alloc", feature = "std", test))]
+fn is_single_case(alphabet: &Alphabet) -> bool {
+    let symbols = alphabet.encode_symbols;
+    !(symbols.iter().any(u8::is_ascii_uppercase) && symbols.iter().any(u8::is_ascii_lowercase))
+}
+
+#[cfg(any(feature = "alloc", feature = "std", test))]
+fn decode_with_options(
+    input_bytes: &[u8],
+    alphabet: &Alphabet,
+    options: DecodeOptions,
+) -> Result<Vec<u8>, DecodeError> {
+    let mut buffer = vec![0; decoded_len(input_bytes.len()).expect("integer multiplication overflow")];
+
+    let o = decode_slice_impl(input_bytes, &mut buffer[..], alphabet, options)?;
+    buffer.truncate(o);
+
+    Ok(buffer)
+}
+
+///Decode the output of [crate::encode::encode_framed] using the specified [Alphabet].
+///The leading symbol must match the number of octets in the trailing partial group,
+///otherwise [DecodeError::InvalidByte] is returned for it. Unused trailing bits must be zero.
+///An empty input lacks the leading symbol and yields [DecodeError::InvalidLength] with `len == 0`.
+///Returns a `Result` containing a `Vec<u8>`.
+#[cfg(any(feature = "alloc", feature = "std", test))]
+pub fn decode_framed<T: AsRef<[u8]>>(input: T, alphabet: &Alphabet) -> Result<Vec<u8>, DecodeError> {
+    let input_bytes = input.as_ref();
+    let (&frame, payload) = input_bytes.split_first().ok_or_else(|| invalid_length(0))?;
+    let decoded = decode_with_options(payload, alphabet, DecodeOptions { strict: true, ..Default::default() })
+        .map_err(|e| match e.offset_bytes(1) {
+            DecodeError::InvalidLength { len, remainder } => DecodeError::InvalidLength { len: len + 1, remainder },
+            e => e,
+        })?;
+
+    if alphabet.decode_bytes[frame as usize] as usize != decoded.len() % 5 {
+        return Err(DecodeError::InvalidByte(0, frame));
+    }
+
+    Ok(decoded)
+}
+
+///Decode from string reference as octets using the specified [Alphabet] up to the first
+///invalid byte, e.g. to salvage a corrupted transmission.
+///Returns the octets completed by the valid prefix and the error that stopped decoding, if any.
+///The first invalid byte is reported even if the input length is invalid as well.
+///
+///# Example
+///
+///```rust
+///use rspamd_base32::alphabet::ZBASE32;
+///use rspamd_base32::decode::{decode_prefix, DecodeError};
+///
+///fn main() {
+///    let (bytes, err) = decode_prefix("em3ags7p!", &ZBASE32);
+///    assert_eq!(b"hello", &bytes[..]);
+///    assert_eq!(Some(DecodeError::InvalidByte(8, b'!')), err);
+///}
+///```
+#[cfg(any(feature = "alloc", feature = "std", test))]
+pub fn decode_prefix<T: AsRef<[u8]>>(input: T, alphabet: &Alphabet) -> (Vec<u8>, Option<DecodeError>) {
+    let input_bytes = input.as_ref();
+    let (valid, err) = match input_bytes.iter().position(|&c| alphabet.decode_bytes[c as usize] == 0xff) {
+        Some(i) => (i, Some(DecodeError::InvalidByte(i, input_bytes[i]))),
+        None if !is_valid_encoded_len(input_bytes.len()) => {
+            (input_bytes.len(), Some(invalid_length(input_bytes.len())))
+        }
+        None => (input_bytes.len(), None),
+    };
+
+    // A trailing symbol that completes no octet leaves an invalid length, dropping it
+    // keeps exactly the octets completed by the prefix
+    let mut symbols = if is_valid_encoded_len(valid) { valid } else { valid - 1 };
+    if err.is_some() && alphabet.octet_order == OctetOrder::Reversed {
+        // Octets of a reversed group depend on its length, so only whole groups are kept
+        symbols -= symbols % 8;
+    }
+    let mut buffer = vec![0; exact_decoded_len(symbols)];
+    let o = decode_slice_impl(&input_bytes[..symbols], &mut buffer, alphabet, DecodeOptions::default())
+        .expect("prefix consists of valid symbols");
+    buffer.truncate(o);
+
+    (buffer, err)
+}
+
+///Decode from string reference as octets using the first of `candidates` that accepts
+///every symbol of the input.
+///Returns the decoded octets and the alphabet used, or `None` if no candidate fits.
+///
+///Alphabets sharing most of their symbols may all accept the same input, so candidates
+///should be ordered from the most to the least likely.
+///
+///# Example
+///
+///```rust
+///use rspamd_base32::alphabet::{RFC, ZBASE32};
+///
+///fn main() {
+///    let (alphabet, bytes) = rspamd_base32::decode_auto("NBSWY3DP", &[&ZBASE32, &RFC]).unwrap();
+///    assert_eq!(&RFC, alphabet);
+///    assert_eq!(b"hello", &bytes[..]);
+///}
+///```
+#[cfg(any(feature = "alloc", feature = "std", test))]
+pub fn decode_auto<'a, T: AsRef<[u8]>>(
+    input: T,
+    candidates: &[&'a Alphabet],
+) -> Option<(&'a Alphabet, Vec<u8>)> {
+    candidates.iter().find_map(|&alphabet| {
+        decode_alphabet(input.as_ref(), alphabet).ok().map(|bytes| (alphabet, bytes))
+    })
 }
 
 ///Decode from string reference as octets.
 ///Writes into the supplied `Vec`, which may allocate if its internal buffer isn't big enough.
-///Returns a `Result` containing an empty tuple, aka `()`.
+///Returns a `Result` containing the number of decoded octets, which is the new length of `buffer`.
+///
+///Previous contents of `buffer` are discarded, only its capacity is reused: on success it
+///holds exactly the decoded octets, on any error it is empty. To append instead, use
+///[decode_alphabet_vec_into_at] with the current length of `buffer` as the offset.
 ///
 ///# Example
 ///
@@ -100,11 +598,12 @@ pub fn decode_alphabet<T: AsRef<[u8]>>(
 ///fn main() {
 ///    let mut buffer = Vec::<u8>::new();
 ///    // with the default engine
-///    rspamd_base32::decode_alphabet_vec(
+///    let written = rspamd_base32::decode_alphabet_vec(
 ///        "em3ags7p",
 ///        &mut buffer,
 ///        &rspamd_base32::alphabet::ZBASE32
 ///    ).unwrap();
+///    assert_eq!(5, written);
 ///    println!("{:?}", buffer);
 ///}
 ///```
@@ -113,113 +612,1715 @@ pub fn decode_alphabet_vec<T: AsRef<[u8]>>(
     input: T,
     buffer: &mut Vec<u8>,
     alphabet: &Alphabet,
-) -> Result<(), DecodeError> {
-    let input_bytes = input.as_ref();
+) -> Result<usize, DecodeError> {
+    decode_alphabet_vec_into_at(input, buffer, 0, alphabet)
+}
 
-    let estimate = decoded_len(input_bytes.len()).expect("integer multiplication overflow");
-    buffer.resize(estimate, 0);
+///Decode from string reference as octets, writing into the supplied `Vec` starting at `offset`.
+///Octets before `offset` are kept, anything after it is replaced by the decoded data,
+///so appending to a shared buffer is done with `offset` equal to its length.
+///Returns a `Result` containing the number of decoded octets. On any error `buffer` holds
+///only the octets before `offset`.
+///
+///# Panics
+///
+///Panics if `offset` is greater than the length of `buffer`.
+///
+///# Example
+///
+///```rust
+///use rspamd_base32::alphabet::ZBASE32;
+///use rspamd_base32::decode::decode_alphabet_vec_into_at;
+///
+///fn main() {
+///    let mut buffer = b"key=".to_vec();
+///    let offset = buffer.len();
+///    assert_eq!(Ok(5), decode_alphabet_vec_into_at("em3ags7p", &mut buffer, offset, &ZBASE32));
+///    assert_eq!(b"key=hello", &buffer[..]);
+///}
+///```
+#[cfg(any(feature = "alloc", feature = "std", test))]
+pub fn decode_alphabet_vec_into_at<T: AsRef<[u8]>>(
+    input: T,
+    buffer: &mut Vec<u8>,
+    offset: usize,
+    alphabet: &Alphabet,
+) -> Result<usize, DecodeError> {
+    decode_vec_with_options(input.as_ref(), buffer, offset, alphabet, DecodeOptions::default())
+}
 
-    let mut processed_bits = 0;
-    let mut acc = 0_u32;
-    let mut o = 0_usize;
-    let mut i = o;
-
-    if alphabet.encode_order == EncodeOrder::OrderInversed {
-        for c in input_bytes {
-            if processed_bits >= 8 {
-                // Emit from left to right
-                processed_bits -= 8;
-                buffer[o] = (acc & 0xFF) as u8;
-                o = o + 1;
-                acc = acc >> 8;
-            }
-            let decoded = alphabet.decode_bytes[*c as usize];
-            if decoded == 0xff {
-                return Err(DecodeError::InvalidByte(i, *c));
-            }
+///Decode from string reference as octets like [decode_alphabet_vec], looking symbols up
+///without secret-dependent memory accesses.
+///Returns a `Result` containing the number of decoded octets, which is the new length of `buffer`.
+///
+///Each input byte is classified by scanning all 256 entries of the decoding table with a mask,
+///so the accessed addresses do not depend on the input. This makes decoding roughly an order
+///of magnitude slower than [decode_alphabet_vec] and is meant for secret material such as keys.
+///Invalid input is still reported as soon as it is found, so only the timing of valid input
+///is protected. The output is identical to [decode_alphabet_vec].
+///
+///# Example
+///
+///```rust
+///extern crate rspamd_base32;
+///
+///fn main() {
+///    let mut key = Vec::new();
+///    rspamd_base32::decode_alphabet_vec_ct("em3ags7p", &mut key, &rspamd_base32::alphabet::ZBASE32).unwrap();
+///    assert_eq!(b"hello", &key[..]);
+///}
+///```
+#[cfg(any(feature = "alloc", feature = "std", test))]
+pub fn decode_alphabet_vec_ct<T: AsRef<[u8]>>(
+    input: T,
+    buffer: &mut Vec<u8>,
+    alphabet: &Alphabet,
+) -> Result<usize, DecodeError> {
+    let options = DecodeOptions { const_time: true, ..Default::default() };
+    decode_vec_with_options(input.as_ref(), buffer, 0, alphabet, options)
+}
 
-            acc = ((decoded as u32) << processed_bits) | acc;
-            processed_bits = processed_bits + 5;
-            i = i + 1;
-        }
-        if processed_bits > 0 {
-            buffer[o] = (acc & 0xFF) as u8;
-            o = o + 1;
-        }
+#[cfg(any(feature = "alloc", feature = "std", test))]
+fn decode_vec_with_options(
+    input_bytes: &[u8],
+    buffer: &mut Vec<u8>,
+    offset: usize,
+    alphabet: &Alphabet,
+    options: DecodeOptions,
+) -> Result<usize, DecodeError> {
+    assert!(offset <= buffer.len(), "offset {} is past the end of the buffer of {}", offset, buffer.len());
+    buffer.truncate(offset);
+    if !is_valid_encoded_len(input_bytes.len()) {
+        return Err(invalid_length(input_bytes.len()));
     }
-    else {
-        for c in input_bytes {
-            let decoded = alphabet.decode_bytes[*c as usize];
-            if decoded == 0xff {
-                return Err(DecodeError::InvalidByte(i, *c));
-            }
 
-            acc = (acc << 5) | decoded as u32;
-            processed_bits = processed_bits + 5;
+    buffer.reserve(exact_decoded_len(input_bytes.len()));
 
-            if processed_bits >= 8 {
-                processed_bits = processed_bits - 8;
-                // Emit from right to left
-                buffer[o] = ((acc >> processed_bits) & 0xFF) as u8;
-                o = o + 1;
-                acc = acc & ((1 << processed_bits) - 1);
+    // Whole groups are independent, so decoding through a small on-stack buffer
+    // avoids zero-filling the whole output first
+    let mut chunk_out = [0_u8; VEC_CHUNK_GROUPS * 5];
+    for (n, chunk) in input_bytes.chunks(VEC_CHUNK_GROUPS * 8).enumerate() {
+        let decoded = decode_slice_impl(chunk, &mut chunk_out, alphabet, options);
+        match decoded {
+            Ok(o) => buffer.extend_from_slice(&chunk_out[..o]),
+            Err(e) => {
+                buffer.truncate(offset);
+                return Err(e.offset_bytes(n * VEC_CHUNK_GROUPS * 8));
             }
-
-            i = i + 1;
         }
     }
 
-    buffer.resize(o, 0);
-
-    Ok(())
+    Ok(buffer.len() - offset)
 }
 
-fn decoded_len(bytes_len : usize) -> Option<usize> {
-    let full_chunks = bytes_len / 8;
-    let remainder = bytes_len % 8;
-    full_chunks.checked_mul(5).and_then(|c| c.checked_add(remainder))
-}
+/// Number of groups decoded at once by [decode_alphabet_vec]
+#[cfg(any(feature = "alloc", feature = "std", test))]
+const VEC_CHUNK_GROUPS: usize = 128;
 
-#[cfg(test)]
+///Decode base32 using the specified [Alphabet] into the predefined output slice.
+///Returns a `usize` of how many output bytes are filled or
+///[DecodeError::OutputTooSmall] if `output_buf` cannot hold the decoded data.
+///Performs no allocations, nothing is written past the decoded length, so a field can be
+///decoded directly into a window of a larger buffer.
+///
+///# Example
+///
+///```rust
+///extern crate rspamd_base32;
+///
+///fn main() {
+///    let mut buf = [0_u8; 5];
+///    let len = rspamd_base32::decode_alphabet_slice(
+///        "em3ags7p",
+///        &mut buf,
+///        &rspamd_base32::alphabet::ZBASE32,
+///    ).unwrap();
+///    assert_eq!(b"hello", &buf[..len]);
+///
+///    // Header, decoded field, trailer
+///    let mut packet = *b"id:.....;";
+///    let len = rspamd_base32::decode_alphabet_slice(
+///        "em3ags7p",
+///        &mut packet[3..],
+///        &rspamd_base32::alphabet::ZBASE32,
+///    ).unwrap();
+///    assert_eq!((5, b"id:hello;"), (len, &packet));
+///}
+///```
+pub fn decode_alphabet_slice<T: AsRef<[u8]>>(
+    input: T,
+    output_buf: &mut [u8],
+    alphabet: &Alphabet,
+) -> Result<usize, DecodeError> {
+    decode_slice_impl(input.as_ref(), output_buf, alphabet, DecodeOptions::default())
+}
+
+///Progress of [decode_alphabet_slice_partial]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct DecodeProgress {
+    /// Number of octets written to the output
+    pub written: usize,
+    /// Number of input symbols decoded, decoding resumes from this offset
+    pub consumed: usize,
+}
+
+///Decode base32 using the specified [Alphabet] into the predefined output slice,
+///stopping when it is full instead of failing, e.g. to decode into fixed windows.
+///Returns a [DecodeProgress] with the number of octets written and input symbols consumed.
+///
+///When the whole input does not fit, only the whole 8-symbol groups that fit are decoded,
+///so the rest of the input can be passed to the next call as is. [DecodeError::OutputTooSmall]
+///is returned if not even one group fits. Error offsets refer to the input of this call.
+///Performs no allocations.
+///
+///# Example
+///
+///```rust
+///use rspamd_base32::alphabet::ZBASE32;
+///use rspamd_base32::decode::decode_alphabet_slice_partial;
+///
+///fn main() {
+///    let input = b"em3ags7py376g3tprd";
+///    let mut window = [0_u8; 5];
+///    let mut decoded = Vec::new();
+///    let mut rest = &input[..];
+///    while !rest.is_empty() {
+///        let progress = decode_alphabet_slice_partial(rest, &mut window, &ZBASE32).unwrap();
+///        decoded.extend_from_slice(&window[..progress.written]);
+///        rest = &rest[progress.consumed..];
+///    }
+///    assert_eq!(b"hello world", &decoded[..]);
+///}
+///```
+pub fn decode_alphabet_slice_partial<T: AsRef<[u8]>>(
+    input: T,
+    output_buf: &mut [u8],
+    alphabet: &Alphabet,
+) -> Result<DecodeProgress, DecodeError> {
+    let input_bytes = input.as_ref();
+    let fitting = output_buf.len() / 5 * 8;
+    // Invalid lengths are reported by decoding the whole input
+    let fits_whole = input_bytes.len() <= fitting || exact_decoded_len(input_bytes.len()) <= output_buf.len();
+
+    let consumed = if fits_whole {
+        input_bytes.len()
+    } else if fitting == 0 {
+        let needed = exact_decoded_len(input_bytes.len()).min(5);
+        return Err(DecodeError::OutputTooSmall { needed, got: output_buf.len() });
+    } else {
+        fitting
+    };
+
+    let written = decode_slice_impl(&input_bytes[..consumed], output_buf, alphabet, DecodeOptions::default())?;
+    Ok(DecodeProgress { written, consumed })
+}
+
+///Decode base32 using the specified [Alphabet] into a fixed-size array.
+///Returns [DecodeError::UnexpectedLength] unless the input decodes to exactly `N` octets.
+///Performs no allocations.
+///
+///# Example
+///
+///```rust
+///extern crate rspamd_base32;
+///
+///fn main() {
+///    let key: [u8; 5] = rspamd_base32::decode_to_array(
+///        "em3ags7p",
+///        &rspamd_base32::alphabet::ZBASE32,
+///    ).unwrap();
+///    assert_eq!(b"hello", &key);
+///}
+///```
+pub fn decode_to_array<const N: usize, T: AsRef<[u8]>>(
+    input: T,
+    alphabet: &Alphabet,
+) -> Result<[u8; N], DecodeError> {
+    let input_bytes = input.as_ref();
+
+    if !is_valid_encoded_len(input_bytes.len()) {
+        return Err(invalid_length(input_bytes.len()));
+    }
+
+    let got = exact_decoded_len(input_bytes.len());
+    if got != N {
+        return Err(DecodeError::UnexpectedLength { expected: N, got });
+    }
+
+    let mut output = [0_u8; N];
+    decode_slice_impl(input_bytes, &mut output, alphabet, DecodeOptions::default())?;
+
+    Ok(output)
+}
+
+/// Optional decoding behaviour
+#[derive(Clone, Copy, Debug, Default)]
+struct DecodeOptions {
+    /// Unused trailing bits must be zero
+    strict: bool,
+    /// ASCII whitespace that is not a symbol is ignored
+    skip_whitespace: bool,
+    /// Group separator that is not a symbol is ignored
+    separator: Option<u8>,
+    /// Bytes that are not accepted are looked up in the other ASCII case
+    fold_case: bool,
+    /// Symbols are looked up without input-dependent memory accesses
+    const_time: bool,
+    /// Rejected bytes are reported as unexpected padding or lookalikes of symbols
+    classify: bool,
+}
+
+impl DecodeOptions {
+    fn skips_any(&self) -> bool {
+        self.skip_whitespace || self.separator.is_some()
+    }
+}
+
+fn is_skipped(c: u8, alphabet: &Alphabet, options: &DecodeOptions) -> bool {
+    let ignored = (options.skip_whitespace && matches!(c, b' ' | b'\t' | b'\r' | b'\n'))
+        || options.separator == Some(c);
+    ignored && alphabet.decode_bytes[c as usize] == 0xff
+}
+
+/// Returns the value of `c`, 0xff if it is not accepted
+fn symbol_value(c: u8, alphabet: &Alphabet, options: &DecodeOptions) -> u8 {
+    if options.const_time {
+        return symbol_value_ct(c, &alphabet.decode_bytes);
+    }
+
+    let decoded = alphabet.decode_bytes[c as usize];
+    if decoded == 0xff && options.fold_case {
+        let folded = if c.is_ascii_lowercase() { c.to_ascii_uppercase() } else { c.to_ascii_lowercase() };
+        return alphabet.decode_bytes[folded as usize];
+    }
+    decoded
+}
+
+/// Returns `table[c]`, scanning the whole table with a mask so that the accessed
+/// addresses do not depend on `c`
+fn symbol_value_ct(c: u8, table: &[u8; 256]) -> u8 {
+    let mut result = 0_u8;
+    for (j, &value) in table.iter().enumerate() {
+        // 0xFF when j == c, 0 otherwise: `diff - 1` only borrows from the high bits for zero
+        let diff = (j ^ c as usize) as u32;
+        let mask = (diff.wrapping_sub(1) >> 8) as u8;
+        result |= value & mask;
+    }
+    result
+}
+
+/// Decodes into `output_buf`, reported offsets always refer to `input_bytes`
+fn decode_slice_impl(
+    input_bytes: &[u8],
+    output_buf: &mut [u8],
+    alphabet: &Alphabet,
+    options: DecodeOptions,
+) -> Result<usize, DecodeError> {
+    let symbols = if options.skips_any() {
+        input_bytes.iter().filter(|&&c| !is_skipped(c, alphabet, &options)).count()
+    } else {
+        input_bytes.len()
+    };
+
+    if !is_valid_encoded_len(symbols) {
+        return Err(invalid_length(symbols));
+    }
+
+    let needed = exact_decoded_len(symbols);
+    if output_buf.len() < needed {
+        return Err(DecodeError::OutputTooSmall { needed, got: output_buf.len() });
+    }
+
+    let o = if alphabet.bit_order == BitOrder::LsbFirst {
+        decode_inverted(input_bytes, output_buf, alphabet, &options)?
+    } else {
+        decode_normal(input_bytes, output_buf, alphabet, &options)?
+    };
+
+    if alphabet.octet_order == OctetOrder::Reversed {
+        for group in output_buf[..o].chunks_mut(5) {
+            group.reverse();
+        }
+    }
+
+    Ok(o)
+}
+
+/// Decodes [BitOrder::LsbFirst] symbols, the input length and the output size must be
+/// checked by the caller. Every symbol is placed above the pending bits of the accumulator,
+/// so octets are emitted from its low bits, left to right.
+fn decode_inverted(
+    input_bytes: &[u8],
+    output_buf: &mut [u8],
+    alphabet: &Alphabet,
+    options: &DecodeOptions,
+) -> Result<usize, DecodeError> {
+    let mut processed_bits = 0;
+    let mut acc = 0_u32;
+    let mut o = 0_usize;
+    // Offset of the last decoded symbol
+    let mut last = 0_usize;
+
+    for (i, &c) in input_bytes.iter().enumerate() {
+        if options.skips_any() && is_skipped(c, alphabet, options) {
+            continue;
+        }
+        if processed_bits >= 8 {
+            // Emit from left to right
+            processed_bits -= 8;
+            output_buf[o] = (acc & 0xFF) as u8;
+            o += 1;
+            acc >>= 8;
+        }
+        let decoded = symbol_value(c, alphabet, options);
+        if decoded == 0xff {
+            return Err(invalid_byte(i, c, alphabet, options));
+        }
+
+        // At most one octet is pending here, so the shift is below 8 and `acc`
+        // never holds more than 12 bits regardless of the input length
+        debug_assert!(processed_bits < 8);
+        acc |= (decoded as u32) << processed_bits;
+        processed_bits += 5;
+        last = i;
+    }
+    if processed_bits > 0 {
+        output_buf[o] = (acc & 0xFF) as u8;
+        o += 1;
+
+        if options.strict && acc >> 8 != 0 {
+            return Err(DecodeError::NonCanonical { index: last });
+        }
+    }
+
+    Ok(o)
+}
+
+/// Decodes [BitOrder::MsbFirst] symbols, the input length and the output size must be
+/// checked by the caller. Every symbol is shifted in below the pending bits of the accumulator,
+/// so octets are emitted from its high bits, right to left.
+fn decode_normal(
+    input_bytes: &[u8],
+    output_buf: &mut [u8],
+    alphabet: &Alphabet,
+    options: &DecodeOptions,
+) -> Result<usize, DecodeError> {
+    let mut processed_bits = 0;
+    let mut acc = 0_u32;
+    let mut o = 0_usize;
+    // Offset of the last decoded symbol
+    let mut last = 0_usize;
+
+    for (i, &c) in input_bytes.iter().enumerate() {
+        if options.skips_any() && is_skipped(c, alphabet, options) {
+            continue;
+        }
+        let decoded = symbol_value(c, alphabet, options);
+        if decoded == 0xff {
+            return Err(invalid_byte(i, c, alphabet, options));
+        }
+
+        // `acc` is masked to the pending bits on every flush, so it never exceeds 12 bits
+        debug_assert!(processed_bits < 8 && acc >> processed_bits == 0);
+        acc = (acc << 5) | decoded as u32;
+        processed_bits += 5;
+
+        if processed_bits >= 8 {
+            processed_bits -= 8;
+            // Emit from right to left
+            output_buf[o] = ((acc >> processed_bits) & 0xFF) as u8;
+            o += 1;
+            acc &= (1 << processed_bits) - 1;
+        }
+
+        last = i;
+    }
+
+    if options.strict && acc != 0 {
+        return Err(DecodeError::NonCanonical { index: last });
+    }
+
+    Ok(o)
+}
+
+///Incremental decoder for input that arrives in arbitrary fragments.
+///The partial accumulator is carried between [DecoderState::feed] calls, so the
+///concatenated output equals decoding the concatenated input at once.
+///
+///# Example
+///
+///```rust
+///use rspamd_base32::alphabet::ZBASE32;
+///use rspamd_base32::decode::DecoderState;
+///
+///fn main() {
+///    let mut state = DecoderState::new(&ZBASE32);
+///    let mut out = Vec::new();
+///    state.feed(b"em3a", &mut out).unwrap();
+///    state.feed(b"gs7p", &mut out).unwrap();
+///    state.finish(&mut out).unwrap();
+///    assert_eq!(b"hello", &out[..]);
+///}
+///```
+#[cfg(any(feature = "alloc", feature = "std", test))]
+#[derive(Clone, Debug)]
+pub struct DecoderState<'a> {
+    alphabet: &'a Alphabet,
+    acc: u32,
+    processed_bits: u32,
+    /// Number of symbols fed so far
+    consumed: usize,
+    /// Octets of the current group held back for [OctetOrder::Reversed]
+    group: [u8; 5],
+    group_len: usize,
+}
+
+#[cfg(any(feature = "alloc", feature = "std", test))]
+impl<'a> DecoderState<'a> {
+    /// Creates a decoder for the specified [Alphabet]
+    pub fn new(alphabet: &'a Alphabet) -> Self {
+        DecoderState { alphabet, acc: 0, processed_bits: 0, consumed: 0, group: [0; 5], group_len: 0 }
+    }
+
+    /// Appends a decoded octet, reversed groups are only appended once complete
+    fn emit(&mut self, byte: u8, out: &mut Vec<u8>) {
+        if self.alphabet.octet_order == OctetOrder::Natural {
+            out.push(byte);
+            return;
+        }
+
+        self.group[self.group_len] = byte;
+        self.group_len += 1;
+        if self.group_len == self.group.len() {
+            self.flush_group(out);
+        }
+    }
+
+    fn flush_group(&mut self, out: &mut Vec<u8>) {
+        out.extend(self.group[..self.group_len].iter().rev());
+        self.group_len = 0;
+    }
+
+    /// Decodes a fragment of input appending complete octets to `out`.
+    /// Error offsets refer to the whole input fed so far, the state is unusable after an error.
+    pub fn feed(&mut self, chunk: &[u8], out: &mut Vec<u8>) -> Result<(), DecodeError> {
+        out.reserve(chunk.len() * 5 / 8 + 1);
+
+        for &c in chunk {
+            let decoded = self.alphabet.decode_bytes[c as usize];
+            if decoded == 0xff {
+                return Err(DecodeError::InvalidByte(self.consumed, c));
+            }
+
+            if self.alphabet.bit_order == BitOrder::LsbFirst {
+                if self.processed_bits >= 8 {
+                    // Emit from left to right
+                    self.processed_bits -= 8;
+                    self.emit((self.acc & 0xFF) as u8, out);
+                    self.acc >>= 8;
+                }
+                debug_assert!(self.processed_bits < 8);
+                self.acc |= (decoded as u32) << self.processed_bits;
+                self.processed_bits += 5;
+            }
+            else {
+                debug_assert!(self.processed_bits < 8);
+                self.acc = (self.acc << 5) | decoded as u32;
+                self.processed_bits += 5;
+
+                if self.processed_bits >= 8 {
+                    self.processed_bits -= 8;
+                    // Emit from right to left
+                    self.emit(((self.acc >> self.processed_bits) & 0xFF) as u8, out);
+                    self.acc &= (1 << self.processed_bits) - 1;
+                }
+            }
+
+            self.consumed += 1;
+        }
+
+        Ok(())
+    }
+
+    /// Checks the total input length and appends the trailing octet if any.
+    /// The state is reset and can be reused for another input.
+    pub fn finish(&mut self, out: &mut Vec<u8>) -> Result<(), DecodeError> {
+        let mut state = core::mem::replace(self, DecoderState::new(self.alphabet));
+
+        if !is_valid_encoded_len(state.consumed) {
+            return Err(invalid_length(state.consumed));
+        }
+
+        if state.alphabet.bit_order == BitOrder::LsbFirst && state.processed_bits > 0 {
+            state.emit((state.acc & 0xFF) as u8, out);
+        }
+        state.flush_group(out);
+
+        Ok(())
+    }
+}
+
+///Iterator over decoded octets produced by [decode_iter]
+#[derive(Clone, Debug)]
+pub struct DecodeIter<'a, I> {
+    input: I,
+    alphabet: &'a Alphabet,
+    bytes: [u8; 5],
+    pos: usize,
+    len: usize,
+    /// Number of symbols decoded so far, used to report error offsets
+    consumed: usize,
+    done: bool,
+}
+
+impl<I: Iterator<Item = u8>> Iterator for DecodeIter<'_, I> {
+    type Item = Result<u8, DecodeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pos == self.len {
+            if self.done {
+                return None;
+            }
+
+            // Decode the next group of up to 8 symbols
+            let mut group = [0_u8; 8];
+            let mut group_len = 0;
+            while group_len < group.len() {
+                match self.input.next() {
+                    Some(sym) => {
+                        group[group_len] = sym;
+                        group_len += 1;
+                    }
+                    None => break,
+                }
+            }
+            if group_len < group.len() {
+                self.done = true;
+            }
+
+            match decode_alphabet_slice(&group[..group_len], &mut self.bytes, self.alphabet) {
+                Ok(len) => {
+                    self.len = len;
+                    self.pos = 0;
+                    self.consumed += group_len;
+                }
+                Err(e) => {
+                    self.done = true;
+                    self.pos = 0;
+                    self.len = 0;
+                    return Some(Err(match e.offset_bytes(self.consumed) {
+                        DecodeError::InvalidLength { len, remainder } => {
+                            DecodeError::InvalidLength { len: self.consumed + len, remainder }
+                        }
+                        e => e,
+                    }));
+                }
+            }
+
+            if self.len == 0 {
+                return None;
+            }
+        }
+
+        let byte = self.bytes[self.pos];
+        self.pos += 1;
+        Some(Ok(byte))
+    }
+}
+
+///Decode base32 lazily using the specified [Alphabet].
+///Returns an iterator over decoded octets, no output buffer is allocated.
+///An invalid symbol or length is reported inline as the last item.
+///
+///# Example
+///
+///```rust
+///extern crate rspamd_base32;
+///
+///fn main() {
+///    let decoded: Result<Vec<u8>, _> = rspamd_base32::decode_iter(
+///        b"em3ags7p".iter().copied(),
+///        &rspamd_base32::alphabet::ZBASE32,
+///    ).collect();
+///    assert_eq!(b"hello", &decoded.unwrap()[..]);
+///}
+///```
+pub fn decode_iter<I: IntoIterator<Item = u8>>(input: I, alphabet: &Alphabet) -> DecodeIter<'_, I::IntoIter> {
+    DecodeIter {
+        input: input.into_iter(),
+        alphabet,
+        bytes: [0; 5],
+        pos: 0,
+        len: 0,
+        consumed: 0,
+        done: false,
+    }
+}
+
+///Decode a `u64` encoded by [crate::encode::encode_u64] using the specified [Alphabet].
+///The input is read as big-endian octets, most significant first, so leading zero octets
+///may be omitted. [crate::encode::encode_u64] never emits them, yet they are accepted:
+///the encodings of `[1]` and `[0, 0, 1]` both decode to `1`, so compare decoded values
+///rather than strings.
+///Returns [DecodeError::TooLong] if the input decodes to more than 8 octets and
+///[DecodeError::InvalidLength] for an empty input, which encodes no octet at all.
+pub fn decode_u64<T: AsRef<[u8]>>(input: T, alphabet: &Alphabet) -> Result<u64, DecodeError> {
+    let mut bytes = [0_u8; 8];
+    let len = decode_alphabet_slice(input, &mut bytes, alphabet).map_err(|e| match e {
+        DecodeError::OutputTooSmall { needed, got } => DecodeError::TooLong { needed, max: got },
+        e => e,
+    })?;
+    // The encoder keeps at least one octet even for zero
+    if len == 0 {
+        return Err(invalid_length(0));
+    }
+
+    Ok(bytes[..len].iter().fold(0, |acc, &b| acc << 8 | b as u64))
+}
+
+///Decode a UUID encoded by [crate::encode::encode_uuid] using the specified [Alphabet].
+///Returns [DecodeError::UnexpectedLength] if the input does not decode to exactly 16 octets.
+#[cfg(feature = "uuid")]
+pub fn decode_uuid<T: AsRef<[u8]>>(input: T, alphabet: &Alphabet) -> Result<uuid::Uuid, DecodeError> {
+    decode_to_array(input, alphabet).map(uuid::Uuid::from_bytes)
+}
+
+///Decode an IPv6 address encoded by [crate::encode::encode_ipv6] using the specified [Alphabet].
+///Returns [DecodeError::UnexpectedLength] if the input does not decode to exactly 16 octets.
+#[cfg(any(feature = "std", test))]
+pub fn decode_ipv6<T: AsRef<[u8]>>(input: T, alphabet: &Alphabet) -> Result<std::net::Ipv6Addr, DecodeError> {
+    decode_to_array::<16, _>(input, alphabet).map(std::net::Ipv6Addr::from)
+}
+
+///Checks that input can be decoded using the specified [Alphabet]
+///without decoding or allocating anything.
+///Returns the same `InvalidLength` or first `InvalidByte` error as decoding would.
+///
+///# Example
+///
+///```rust
+///extern crate rspamd_base32;
+///
+///fn main() {
+///    assert!(rspamd_base32::validate("em3ags7p", &rspamd_base32::alphabet::ZBASE32).is_ok());
+///    assert!(rspamd_base32::validate("em3ags7p", &rspamd_base32::alphabet::RFC).is_err());
+///}
+///```
+pub fn validate<T: AsRef<[u8]>>(input: T, alphabet: &Alphabet) -> Result<(), DecodeError> {
+    let input_bytes = input.as_ref();
+
+    if !is_valid_encoded_len(input_bytes.len()) {
+        return Err(invalid_length(input_bytes.len()));
+    }
+
+    match input_bytes.iter().position(|&c| alphabet.decode_bytes[c as usize] == 0xff) {
+        Some(i) => Err(DecodeError::InvalidByte(i, input_bytes[i])),
+        None => Ok(()),
+    }
+}
+
+///Checks every byte of input against the specified [Alphabet], unlike [validate] this does
+///not stop at the first invalid one, e.g. to highlight all of them in a form.
+///Returns a `Vec<bool>` that is `true` for bytes accepted by the decoder.
+///
+///# Example
+///
+///```rust
+///extern crate rspamd_base32;
+///
+///fn main() {
+///    let mask = rspamd_base32::classify("em!a", &rspamd_base32::alphabet::ZBASE32);
+///    assert_eq!(vec![true, true, false, true], mask);
+///}
+///```
+#[cfg(any(feature = "alloc", feature = "std", test))]
+pub fn classify(input: &str, alphabet: &Alphabet) -> Vec<bool> {
+    input.bytes().map(|c| alphabet.contains(c)).collect()
+}
+
+///Checks that `encoded` is the encoding of `bytes` using the specified [Alphabet]
+///without decoding or allocating anything.
+///Symbols are compared by value, so case-folded variants and aliases match, and the
+///comparison stops at the first mismatch. Invalid input is never equal to anything.
+///
+///# Example
+///
+///```rust
+///extern crate rspamd_base32;
+///
+///fn main() {
+///    let alphabet = &rspamd_base32::alphabet::ZBASE32;
+///    assert!(rspamd_base32::eq_encoded("em3ags7p", b"hello", alphabet));
+///    assert!(!rspamd_base32::eq_encoded("em3ags7p", b"hellO", alphabet));
+///}
+///```
+pub fn eq_encoded(encoded: &str, bytes: &[u8], alphabet: &Alphabet) -> bool {
+    let mut expected = encode_iter(bytes.iter().copied(), alphabet);
+
+    for &c in encoded.as_bytes() {
+        let decoded = alphabet.decode_bytes[c as usize];
+        match expected.next() {
+            Some(sym) if decoded != 0xff && decoded == alphabet.decode_bytes[sym as usize] => {}
+            _ => return false,
+        }
+    }
+
+    expected.next().is_none()
+}
+
+fn invalid_length(len: usize) -> DecodeError {
+    DecodeError::InvalidLength { len, remainder: len % 8 }
+}
+
+///Checks that the encoded length leaves a remainder that can correspond to
+///a whole number of octets: 1, 2, 3 or 4 trailing bytes take 2, 4, 5 or 7 symbols.
+///Decoders return [DecodeError::InvalidLength] for any other length.
+pub const fn is_valid_encoded_len(bytes_len: usize) -> bool {
+    matches!(bytes_len % 8, 0 | 2 | 4 | 5 | 7)
+}
+
+///Returns decoded length upper bound for given encoded input length,
+///suitable for pre-sizing buffers passed to [decode_alphabet_vec].
+///Decoded data is shorter than its encoding, so this never returns `None`
+///and decoding never panics on overflow, on 32-bit targets as well
+pub fn decoded_len(bytes_len : usize) -> Option<usize> {
+    let full_chunks = bytes_len / 8;
+    let remainder = bytes_len % 8;
+    full_chunks.checked_mul(5).and_then(|c| c.checked_add(remainder))
+}
+
+///Returns the exact decoded length of `encoded`, not counting trailing padding characters,
+///`=` unless the alphabet defines its own. Returns `None` if the unpadded length is invalid
+///or the padding character is an alphabet symbol.
+///
+///# Example
+///
+///```rust
+///use rspamd_base32::alphabet::RFC;
+///use rspamd_base32::decode::decoded_len_padded;
+///
+///fn main() {
+///    assert_eq!(Some(3), decoded_len_padded(b"NBSWY===", &RFC));
+///}
+///```
+pub fn decoded_len_padded(encoded: &[u8], alphabet: &Alphabet) -> Option<usize> {
+    let pad = alphabet.padding.unwrap_or(PAD);
+    if alphabet.contains(pad) {
+        return None;
+    }
+    let data_len = encoded.iter().rposition(|&c| c != pad).map_or(0, |pos| pos + 1);
+    is_valid_encoded_len(data_len).then(|| exact_decoded_len(data_len))
+}
+
+/// Exact number of octets produced by a valid encoded input of length `bytes_len`
+fn exact_decoded_len(bytes_len: usize) -> usize {
+    // Each trailing symbol adds 5 bits, incomplete octets are dropped
+    bytes_len / 8 * 5 + (bytes_len % 8) * 5 / 8
+}
+
+#[cfg(test)]
 mod tests {
     use crate::encode::*;
     use crate::decode::*;
     use crate::alphabet::*;
 
     #[test]
-    fn simple_decode_zbase() {
+    fn simple_decode_zbase() {
+        assert_eq!(
+            "test123".as_bytes(),
+            decode("wm3g84fg13cy").expect("undecoded!"),
+        );
+        assert_eq!(
+            "hello".as_bytes(),
+            decode("em3ags7p").expect("undecoded"),
+        );
+    }
+
+    #[test]
+    fn simple_encode_decode_zbase() {
+        assert_eq!("test123".as_bytes(),
+            decode(encode("test123")).expect("undecoded"));
+    }
+
+    #[test]
+    fn simple_decode_rfc() {
+        assert_eq!(
+            "test123".as_bytes(),
+            decode_alphabet("ORSXG5BRGIZQ", &RFC).expect("undecoded!"),
+        );
+        assert_eq!(
+            "hello".as_bytes(),
+            decode_alphabet("NBSWY3DP", &RFC).expect("undecoded"),
+        );
+    }
+
+    #[test]
+    fn simple_encode_decode_rfc() {
+        assert_eq!("test123".as_bytes(),
+                   decode_alphabet(encode_alphabet("test123", &RFC),
+                                   &RFC).expect("undecoded"));
+    }
+
+    #[test]
+    fn decoded_len_bounds() {
+        assert_eq!(Some(0), decoded_len(0));
+        assert_eq!(Some(2), decoded_len(2));
+        assert_eq!(Some(5), decoded_len(8));
+        assert_eq!(Some(9), decoded_len(12));
+        for len in [0, 1, 5, 100, 1023] {
+            let enc_len = encode_alphabet(vec![0_u8; len], &RFC).len();
+            assert!(decoded_len(enc_len).unwrap() >= len);
+        }
+        // Decoded data is always shorter than encoded, so even the largest
+        // length (which is `u32::MAX` on 32-bit targets) must not overflow
+        assert_eq!(Some(usize::MAX / 8 * 5 + 7), decoded_len(usize::MAX));
+    }
+
+    #[test]
+    fn decode_high_byte() {
+        assert_eq!(
+            Err(DecodeError::InvalidByte(0, 0xFF)),
+            decode([0xFF_u8, b'y']),
+        );
+        assert_eq!(
+            Err(DecodeError::InvalidByte(0, 0xFF)),
+            decode_alphabet([0xFF_u8, b'A'], &RFC),
+        );
+    }
+
+    #[test]
+    fn decode_slice_exact_fit() {
+        let mut buf = [0_u8; 5];
+        assert_eq!(Ok(5), decode_alphabet_slice("em3ags7p", &mut buf, &ZBASE32));
+        assert_eq!(b"hello", &buf);
+
+        let mut buf = [0_u8; 7];
+        assert_eq!(Ok(7), decode_alphabet_slice("ORSXG5BRGIZQ", &mut buf, &RFC));
+        assert_eq!(b"test123", &buf);
+    }
+
+    #[test]
+    fn decode_slice_window() {
+        let mut buf = [0xaa_u8; 16];
+        assert_eq!(Ok(5), decode_alphabet_slice("em3ags7p", &mut buf[4..9], &ZBASE32));
+        assert_eq!(b"hello", &buf[4..9]);
+        assert!(buf[..4].iter().chain(&buf[9..]).all(|&b| b == 0xaa));
+
+        // A larger window is filled from its start only
+        assert_eq!(Ok(5), decode_alphabet_slice("NBSWY3DP", &mut buf[9..], &RFC));
+        assert_eq!(b"hellohello", &buf[4..14]);
+        assert_eq!([0xaa; 2], buf[14..]);
+        assert_eq!(
+            Err(DecodeError::OutputTooSmall { needed: 5, got: 4 }),
+            decode_alphabet_slice("NBSWY3DP", &mut buf[12..], &RFC),
+        );
+        assert_eq!([0xaa; 2], buf[14..]);
+    }
+
+    #[test]
+    fn decode_slice_too_small() {
+        let mut buf = [0_u8; 4];
+        assert_eq!(
+            Err(DecodeError::OutputTooSmall { needed: 5, got: 4 }),
+            decode_alphabet_slice("em3ags7p", &mut buf, &ZBASE32),
+        );
+        assert_eq!(
+            Err(DecodeError::OutputTooSmall { needed: 7, got: 0 }),
+            decode_alphabet_slice("ORSXG5BRGIZQ", &mut [], &RFC),
+        );
+    }
+
+    #[test]
+    fn decode_padded() {
+        for padding in [Padding::Require, Padding::Accept, Padding::Forbid] {
+            assert_eq!(
+                "hello".as_bytes(),
+                decode_alphabet_padded("NBSWY3DP", &RFC, padding).expect("undecoded"),
+            );
+        }
+        for padding in [Padding::Require, Padding::Accept] {
+            assert_eq!(
+                "hello world".as_bytes(),
+                decode_alphabet_padded("NBSWY3DPEB3W64TMMQ======", &RFC, padding).expect("undecoded"),
+            );
+        }
+        assert_eq!(
+            "hello world".as_bytes(),
+            decode_alphabet_padded("NBSWY3DPEB3W64TMMQ", &RFC, Padding::Accept).expect("undecoded"),
+        );
+        assert_eq!(
+            Err(DecodeError::InvalidPadding(18)),
+            decode_alphabet_padded("NBSWY3DPEB3W64TMMQ", &RFC, Padding::Require),
+        );
+        assert_eq!(
+            Err(DecodeError::InvalidPadding(2)),
+            decode_alphabet_padded("ME======", &RFC, Padding::Forbid),
+        );
+    }
+
+    #[test]
+    fn decoded_len_of_padded() {
+        // Python's base64.b32encode output
+        for (encoded, plain) in [
+            ("NBSWY3A=", "hell"),
+            ("NBSWY===", "hel"),
+            ("NBSQ====", "he"),
+            ("NA======", "h"),
+            ("NBSWY3DPEB3W64TMMQ======", "hello world"),
+            ("NBSWY3DP", "hello"),
+            ("", ""),
+        ] {
+            assert_eq!(Some(plain.len()), decoded_len_padded(encoded.as_bytes(), &RFC), "{}", encoded);
+            assert_eq!(
+                plain.as_bytes(),
+                decode_alphabet_padded(encoded, &RFC, Padding::Require).expect("undecoded"),
+            );
+        }
+        assert_eq!(None, decoded_len_padded(b"NBS=====", &RFC));
+        assert_eq!(None, decoded_len_padded(b"N=======", &RFC));
+    }
+
+    #[test]
+    fn decode_custom_padding() {
+        let alphabet = AlphabetBuilder::new()
+            .symbols("ABCDEFGHIJKLMNOPQRSTUVWXYZ234567")
+            .padding(Some(b'.'))
+            .build()
+            .unwrap();
         assert_eq!(
-            "test123".as_bytes(),
-            decode("wm3g84fg13cy").expect("undecoded!"),
+            "a".as_bytes(),
+            decode_alphabet_padded("ME......", &alphabet, Padding::Require).expect("undecoded"),
         );
+    }
+
+    #[test]
+    fn decode_padded_rejects_symbol() {
+        // Trailing `=` symbols are data here, stripping them would decode different octets
+        let alphabet = Alphabet::from_str("ABCDEFGHIJKLMNOPQRSTUVWXYZ23456=").unwrap();
+        for padding in [Padding::Require, Padding::Accept, Padding::Forbid] {
+            assert_eq!(
+                Err(DecodeError::PaddingCollision(b'=')),
+                decode_alphabet_padded("ME======", &alphabet, padding),
+            );
+        }
+        assert_eq!(Ok(vec![0x67, 0xff, 0xff, 0xff, 0xff]), decode_alphabet("M=======", &alphabet));
+        assert_eq!(None, decoded_len_padded(b"M=======", &alphabet));
+    }
+
+    #[test]
+    fn decode_malformed_padding() {
+        for padding in [Padding::Require, Padding::Accept] {
+            // interior padding
+            assert_eq!(
+                Err(DecodeError::InvalidPadding(2)),
+                decode_alphabet_padded("NB=SWY3DP=======", &RFC, padding),
+            );
+            // wrong amount of padding
+            assert_eq!(
+                Err(DecodeError::InvalidPadding(2)),
+                decode_alphabet_padded("ME=====", &RFC, padding),
+            );
+            assert_eq!(
+                Err(DecodeError::InvalidPadding(8)),
+                decode_alphabet_padded("NBSWY3DP========", &RFC, padding),
+            );
+        }
+    }
+
+    #[test]
+    fn decode_strict() {
+        // Lenient decoding ignores unused trailing bits
+        assert_eq!("a".as_bytes(), decode_alphabet("MF", &RFC).expect("undecoded"));
+        assert_eq!("a".as_bytes(), decode_alphabet("bm", &ZBASE32).expect("undecoded"));
+
+        assert_eq!("a".as_bytes(), decode_alphabet_strict("ME", &RFC).expect("undecoded"));
+        assert_eq!("a".as_bytes(), decode_alphabet_strict("bd", &ZBASE32).expect("undecoded"));
         assert_eq!(
-            "hello".as_bytes(),
-            decode("em3ags7p").expect("undecoded"),
+            Err(DecodeError::NonCanonical { index: 1 }),
+            decode_alphabet_strict("MF", &RFC),
+        );
+        assert_eq!(
+            Err(DecodeError::NonCanonical { index: 1 }),
+            decode_alphabet_strict("bm", &ZBASE32),
+        );
+        assert_eq!(
+            Err(DecodeError::NonCanonical { index: 11 }),
+            decode_alphabet_strict("ORSXG5BRGIZR", &RFC),
         );
     }
 
     #[test]
-    fn simple_encode_decode_zbase() {
-        assert_eq!("test123".as_bytes(),
-            decode(encode("test123")).expect("undecoded"));
+    fn decode_strict_truncated() {
+        // "hello" is "em3ags7p", dropping the last symbol still leaves a valid length
+        assert_eq!("hell".as_bytes(), decode("em3ags7").expect("undecoded"));
+        assert_eq!(
+            Err(DecodeError::NonCanonical { index: 6 }),
+            decode_alphabet_strict("em3ags7", &ZBASE32),
+        );
+        // "test123" is "wm3g84fg13cy"
+        assert_eq!(
+            Err(DecodeError::NonCanonical { index: 9 }),
+            decode_alphabet_strict("wm3g84fg13", &ZBASE32),
+        );
+        // "ORSXG5BRGIZQ" cut after 'I' leaves no unused bits set and is indistinguishable
+        assert_eq!(
+            "test12".as_bytes(),
+            decode_alphabet_strict("ORSXG5BRGI", &RFC).expect("undecoded"),
+        );
     }
 
     #[test]
-    fn simple_decode_rfc() {
+    fn decode_strict_canonical_roundtrip() {
+        for alphabet in [&ZBASE32, &RFC] {
+            for len in 0..40 {
+                let input: Vec<u8> = (0..len).map(|i| (i * 251) as u8).collect();
+                assert_eq!(
+                    input,
+                    decode_alphabet_strict(encode_alphabet(&input, alphabet), alphabet).expect("undecoded"),
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn decode_lenient_whitespace() {
+        let clean = encode(vec![0x5a_u8; 100]);
+        let wrapped: String = clean
+            .as_bytes()
+            .chunks(64)
+            .map(|line| format!("{}\r\n", std::str::from_utf8(line).unwrap()))
+            .collect();
         assert_eq!(
-            "test123".as_bytes(),
-            decode_alphabet("ORSXG5BRGIZQ", &RFC).expect("undecoded!"),
+            decode(&clean).expect("undecoded"),
+            decode_alphabet_lenient(&wrapped, &ZBASE32).expect("undecoded"),
         );
         assert_eq!(
             "hello".as_bytes(),
-            decode_alphabet("NBSWY3DP", &RFC).expect("undecoded"),
+            decode_alphabet_lenient(" NBSW Y3DP\t", &RFC).expect("undecoded"),
+        );
+        assert_eq!(
+            Err(DecodeError::InvalidByte(6, b'!')),
+            decode_alphabet_lenient("NB\n\nSW!3DP", &RFC),
+        );
+        assert_eq!(
+            Err(DecodeError::NonCanonical { index: 2 }),
+            decode_with_options(b"M\nF \n", &RFC, DecodeOptions { strict: true, skip_whitespace: true, separator: None, fold_case: false, const_time: false, classify: false }),
         );
+        assert!(decode_alphabet("NBSW Y3DP", &RFC).is_err());
     }
 
     #[test]
-    fn simple_encode_decode_rfc() {
-        assert_eq!("test123".as_bytes(),
-                   decode_alphabet(encode_alphabet("test123", &RFC),
-                                   &RFC).expect("undecoded"));
+    fn decode_array() {
+        assert_eq!(Ok(*b"hello"), decode_to_array::<5, _>("em3ags7p", &ZBASE32));
+        let key = [0xa5_u8; 32];
+        assert_eq!(Ok(key), decode_to_array(encode_alphabet(key, &RFC), &RFC));
+        assert_eq!(
+            Err(DecodeError::UnexpectedLength { expected: 6, got: 5 }),
+            decode_to_array::<6, _>("em3ags7p", &ZBASE32),
+        );
+        assert_eq!(
+            Err(DecodeError::UnexpectedLength { expected: 4, got: 5 }),
+            decode_to_array::<4, _>("em3ags7p", &ZBASE32),
+        );
+        assert_eq!(
+            Err(DecodeError::InvalidByte(1, b'!')),
+            decode_to_array::<5, _>("e!3ags7p", &ZBASE32),
+        );
+    }
+
+    #[test]
+    fn decoder_state_every_split() {
+        for alphabet in [&ZBASE32, &RFC] {
+            for len in 0..24 {
+                let input: Vec<u8> = (0..len).map(|i| (i * 97 + 13) as u8).collect();
+                let encoded = encode_alphabet(&input, alphabet);
+                for split in 0..=encoded.len() {
+                    let (head, tail) = encoded.as_bytes().split_at(split);
+                    let mut state = DecoderState::new(alphabet);
+                    let mut out = Vec::new();
+                    state.feed(head, &mut out).expect("undecoded");
+                    state.feed(tail, &mut out).expect("undecoded");
+                    state.finish(&mut out).expect("undecoded");
+                    assert_eq!(decode_alphabet(&encoded, alphabet).unwrap(), out);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn decoder_state_errors() {
+        let mut state = DecoderState::new(&ZBASE32);
+        let mut out = Vec::new();
+        state.feed(b"em3", &mut out).expect("undecoded");
+        assert_eq!(Err(DecodeError::InvalidByte(4, b'!')), state.feed(b"a!", &mut out));
+
+        let mut state = DecoderState::new(&ZBASE32);
+        state.feed(b"em3", &mut out).expect("undecoded");
+        assert_eq!(
+            Err(DecodeError::InvalidLength { len: 3, remainder: 3 }),
+            state.finish(&mut out),
+        );
+    }
+
+    #[test]
+    fn decode_classified_errors() {
+        assert_eq!(Err(DecodeError::UnexpectedPadding(2)), decode_alphabet_lenient("ME======", &RFC));
+        assert_eq!(Err(DecodeError::UnexpectedPadding(3)), decode_human("M E======", &RFC));
+        let alphabet = AlphabetBuilder::new()
+            .symbols("ABCDEFGHIJKLMNOPQRSTUVWXYZ234567")
+            .padding(Some(b'.'))
+            .build()
+            .unwrap();
+        assert_eq!(Err(DecodeError::UnexpectedPadding(2)), decode_alphabet_lenient("ME......", &alphabet));
+        // `=` is an ordinary foreign byte for a custom padding character
+        assert_eq!(Err(DecodeError::InvalidByte(2, b'=')), decode_alphabet_lenient("ME======", &alphabet));
+
+        for (input, alphabet, index, byte, interpreted_as) in [
+            ("NBSWY3D0", &RFC, 7, b'0', b'O'),
+            ("NBSW8", &RFC, 4, b'8', b'B'),
+            ("em3ags7l", &ZBASE32, 7, b'l', b'1'),
+            ("em3ags0", &ZBASE32, 6, b'0', b'o'),
+            ("2m3ags7p", &ZBASE32, 0, b'2', b'z'),
+        ] {
+            let err = DecodeError::ConfusableByte { index, byte, interpreted_as };
+            assert_eq!(Err(err.clone()), decode_alphabet_lenient(input, alphabet), "{}", input);
+            assert_eq!(Err(err.clone()), decode_human(input, alphabet), "{}", input);
+            assert_eq!(Err(err), decode_alphabet_padded(input, alphabet, Padding::Accept), "{}", input);
+
+            // The default API reports the byte as invalid
+            let err = DecodeError::InvalidByte(index, byte);
+            assert_eq!(Err(err.clone()), decode_alphabet(input, alphabet), "{}", input);
+            assert_eq!(Err(err.clone()), decode_alphabet_strict(input, alphabet), "{}", input);
+            assert_eq!(Err(err.clone()), validate(input, alphabet), "{}", input);
+            assert_eq!(Some(err), decode_prefix(input, alphabet).1, "{}", input);
+        }
+        assert_eq!(Err(DecodeError::InvalidByte(2, b'=')), decode_alphabet("ME======", &RFC));
+        assert_eq!(Err(DecodeError::InvalidByte(1, b'=')), validate("M=", &RFC));
+
+        // Offsets refer to the whole input of chunked decoding
+        let mut input = encode_alphabet(vec![0_u8; 1000], &RFC).into_bytes();
+        input[1500] = b'0';
+        assert_eq!(
+            Err(DecodeError::ConfusableByte { index: 1500, byte: b'0', interpreted_as: b'O' }),
+            decode_alphabet_lenient(&input, &RFC),
+        );
+        assert_eq!(
+            "Invalid byte 48, offset 3, did you mean 'O'?",
+            decode_human("NBS0", &RFC).unwrap_err().to_string(),
+        );
+    }
+
+    #[test]
+    fn validate_input() {
+        assert_eq!(Ok(()), validate("em3ags7p", &ZBASE32));
+        assert_eq!(Ok(()), validate("", &ZBASE32));
+        assert_eq!(Ok(()), validate("NBSWY3DP", &RFC));
+        assert_eq!(
+            Err(DecodeError::InvalidByte(2, b'1')),
+            validate("NB1WY3DP", &RFC),
+        );
+        assert_eq!(
+            Err(DecodeError::InvalidLength { len: 3, remainder: 3 }),
+            validate("NBS", &RFC),
+        );
+    }
+
+    #[test]
+    fn decode_invalid_length_remainder() {
+        match decode("ybndrfg8e") {
+            Err(DecodeError::InvalidLength { len, remainder }) => {
+                assert_eq!(9, len);
+                assert_eq!(1, remainder);
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+        assert_eq!(
+            "Encoded text cannot have 6 trailing symbols: length = 6",
+            decode("ybndrf").unwrap_err().to_string(),
+        );
+    }
+
+    #[test]
+    fn decode_invalid_length() {
+        for alphabet in [&ZBASE32, &RFC] {
+            assert_eq!(
+                Err(DecodeError::InvalidLength { len: 1, remainder: 1 }),
+                decode_alphabet("y", alphabet),
+            );
+            assert_eq!(
+                Err(DecodeError::InvalidLength { len: 3, remainder: 3 }),
+                decode_alphabet("ybn", alphabet),
+            );
+            assert_eq!(
+                Err(DecodeError::InvalidLength { len: 14, remainder: 6 }),
+                decode_alphabet("ybndrfg8ejkmcp", alphabet),
+            );
+        }
+    }
+
+    #[test]
+    fn long_same_symbol_roundtrip() {
+        // Long runs of every symbol exercise the accumulator for both bit orders
+        for alphabet in [&ZBASE32, &RFC] {
+            for &sym in alphabet.encode_symbols.iter() {
+                for len in [8, 16, 800, 8000] {
+                    let input = vec![sym; len];
+                    let decoded = decode_alphabet_strict(&input, alphabet).unwrap();
+                    assert_eq!(len / 8 * 5, decoded.len());
+                    assert_eq!(input, encode_alphabet(&decoded, alphabet).into_bytes());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn eq_encoded_compares_without_decoding() {
+        let data: Vec<u8> = (0..40).collect();
+        for alphabet in [&ZBASE32, &RFC] {
+            for len in 0..data.len() {
+                let encoded = encode_alphabet(&data[..len], alphabet);
+                assert!(eq_encoded(&encoded, &data[..len], alphabet));
+                // Truncated and extended inputs
+                assert!(!eq_encoded(&encoded, &data[..len + 1], alphabet));
+                if len > 0 {
+                    assert!(!eq_encoded(&encoded, &data[..len - 1], alphabet));
+                }
+            }
+        }
+
+        assert!(!eq_encoded("em3ags7p", b"help!", &ZBASE32));
+        assert!(!eq_encoded("em3ags7y", b"hello", &ZBASE32));
+        // Invalid bytes are a mismatch rather than an error
+        assert!(!eq_encoded("em3ags7!", b"hello", &ZBASE32));
+        assert!(!eq_encoded("em3ags7p\n", b"hello", &ZBASE32));
+        assert!(!eq_encoded("NBSWY3DP", b"hello", &ZBASE32));
+        // Case-folded symbols and aliases match by value
+        assert!(eq_encoded("d1jprv3f", b"hello", &CROCKFORD));
+        assert!(eq_encoded("i0", &[0x08], &CROCKFORD));
+        assert!(!eq_encoded("nbswy3dp", b"hello", &RFC));
+    }
+
+    #[test]
+    fn invalid_byte_offset() {
+        // The offset is the 0-based position of the offending symbol for both bit orders
+        for alphabet in [&ZBASE32, &RFC] {
+            let encoded = encode_alphabet("hello", alphabet).into_bytes();
+            for pos in [0, 1, encoded.len() - 1] {
+                let mut input = encoded.clone();
+                input[pos] = b'!';
+                let err = DecodeError::InvalidByte(pos, b'!');
+                assert_eq!(Err(err.clone()), decode_alphabet(&input, alphabet));
+                assert_eq!(Err(err.clone()), decode_alphabet_slice(&input, &mut [0; 5], alphabet));
+                assert_eq!(Err(err.clone()), validate(&input, alphabet));
+
+                let mut state = DecoderState::new(alphabet);
+                let (head, tail) = input.split_at(3);
+                let mut out = Vec::new();
+                let fed = state.feed(head, &mut out).and_then(|_| state.feed(tail, &mut out));
+                assert_eq!(Err(err), fed);
+            }
+        }
+    }
+
+    #[test]
+    fn decode_grouped() {
+        assert_eq!(Ok(b"hello".to_vec()), decode_alphabet_grouped("em3a-gs7p", &ZBASE32, b'-'));
+        assert_eq!(Ok(b"hello".to_vec()), decode_alphabet_grouped("-em3ags7p--", &ZBASE32, b'-'));
+        // Only the configured separator is skipped
+        assert_eq!(
+            Err(DecodeError::InvalidByte(4, b' ')),
+            decode_alphabet_grouped("em3a gs7", &ZBASE32, b'-')
+        );
+        // A separator that is a symbol keeps its meaning
+        assert_eq!(decode_alphabet("em3ags7p", &ZBASE32), decode_alphabet_grouped("em3ags7p", &ZBASE32, b'a'));
+    }
+
+    #[test]
+    #[cfg(feature = "uuid")]
+    fn uuid_roundtrip() {
+        use rand::prelude::*;
+
+        let random = uuid::Uuid::from_bytes(rand::rngs::StdRng::from_entropy().gen());
+        for u in [uuid::Uuid::nil(), uuid::Uuid::max(), random] {
+            for alphabet in [&ZBASE32, &RFC] {
+                let encoded = encode_uuid(&u, alphabet);
+                assert_eq!(26, encoded.len());
+                assert_eq!(Ok(u), decode_uuid(&encoded, alphabet));
+            }
+        }
+        assert_eq!(
+            Err(DecodeError::UnexpectedLength { expected: 16, got: 5 }),
+            decode_uuid("em3ags7p", &ZBASE32)
+        );
+    }
+
+    #[test]
+    fn ipv6_roundtrip() {
+        use std::net::Ipv6Addr;
+
+        for addr in [Ipv6Addr::LOCALHOST, "2001:db8::1".parse().unwrap(), Ipv6Addr::UNSPECIFIED] {
+            for alphabet in [&ZBASE32, &DNS32] {
+                let encoded = encode_ipv6(addr, alphabet);
+                assert_eq!(26, encoded.len());
+                assert_eq!(Ok(addr), decode_ipv6(&encoded, alphabet));
+            }
+        }
+        assert_eq!(
+            Err(DecodeError::UnexpectedLength { expected: 16, got: 4 }),
+            decode_ipv6(encode_alphabet([127, 0, 0, 1], &ZBASE32), &ZBASE32)
+        );
+    }
+
+    #[test]
+    fn into_io_error() {
+        fn decode_io(input: &str) -> std::io::Result<Vec<u8>> {
+            Ok(decode_alphabet(input, &ZBASE32)?)
+        }
+
+        let err = decode_io("em3!gs7p").unwrap_err();
+        assert_eq!(std::io::ErrorKind::InvalidData, err.kind());
+        assert_eq!("Invalid byte 33, offset 3.", err.to_string());
+        assert_eq!(
+            Some(&DecodeError::InvalidByte(3, b'!')),
+            err.get_ref().and_then(|e| e.downcast_ref::<DecodeError>())
+        );
+    }
+
+    #[test]
+    fn decode_human_input() {
+        assert_eq!(Ok(b"hello".to_vec()), decode_human("NB-sw y3dp", &RFC));
+        assert_eq!(Ok(b"hello".to_vec()), decode_human("EM3A-GS7P\n", &ZBASE32));
+        assert_eq!(Err(DecodeError::InvalidByte(4, b'_')), decode_human("NBSW_Y3D", &RFC));
+        // Both cases are distinct symbols of the word-safe alphabet
+        let encoded = encode_alphabet("hello", &WORDSAFE);
+        assert_eq!(Ok(b"hello".to_vec()), decode_human(&encoded, &WORDSAFE));
+        assert_ne!(Ok(b"hello".to_vec()), decode_human(encoded.to_ascii_uppercase(), &WORDSAFE));
+    }
+
+    #[test]
+    fn decode_iter_same_as_decode() {
+        for alphabet in [&ZBASE32, &RFC] {
+            for len in 0..40 {
+                let input: Vec<u8> = (0..len).map(|i| (i * 37) as u8).collect();
+                let encoded = encode_alphabet(&input, alphabet);
+                let decoded: Result<Vec<u8>, _> = decode_iter(encoded.bytes(), alphabet).collect();
+                assert_eq!(decode_alphabet(&encoded, alphabet), decoded);
+            }
+        }
+    }
+
+    #[test]
+    fn decode_iter_errors() {
+        let mut iter = decode_iter(b"em3ags7pe!".iter().copied(), &ZBASE32);
+        assert_eq!(b"hello".to_vec(), iter.by_ref().take(5).collect::<Result<Vec<u8>, _>>().unwrap());
+        assert_eq!(Some(Err(DecodeError::InvalidByte(9, b'!'))), iter.next());
+        assert_eq!(None, iter.next());
+
+        let decoded: Result<Vec<u8>, _> = decode_iter(b"em3ags7py".iter().copied(), &ZBASE32).collect();
+        assert_eq!(Err(DecodeError::InvalidLength { len: 9, remainder: 1 }), decoded);
+    }
+
+    #[test]
+    fn decode_auto_picks_alphabet() {
+        let candidates = [&ZBASE32, &RFC];
+        assert_eq!(Some((&ZBASE32, b"hello".to_vec())), decode_auto("em3ags7p", &candidates));
+        assert_eq!(Some((&RFC, b"hello".to_vec())), decode_auto("NBSWY3DP", &candidates));
+        assert_eq!(None, decode_auto("em3ags7!", &candidates));
+        assert_eq!(None, decode_auto("NBSWY3DP", &[]));
+        // Invalid length fails for every candidate
+        assert_eq!(None, decode_auto("NBSWY3", &candidates));
+    }
+
+    #[test]
+    fn decode_vec_reuses_buffer() {
+        let data: Vec<u8> = (0..=255).cycle().take(3001).collect();
+        let mut buffer = vec![0xaa; 10000];
+        for alphabet in [&ZBASE32, &RFC] {
+            for len in [0, 1, 5, 639, 640, 641, 3001] {
+                let encoded = encode_alphabet(&data[..len], alphabet);
+                assert_eq!(Ok(len), decode_alphabet_vec(&encoded, &mut buffer, alphabet));
+                assert_eq!(decode_alphabet(&encoded, alphabet).unwrap(), buffer);
+            }
+        }
+
+        // Offsets are reported relative to the whole input
+        let mut encoded = encode(&data).into_bytes();
+        encoded[4000] = b'!';
+        assert_eq!(
+            Err(DecodeError::InvalidByte(4000, b'!')),
+            decode_alphabet_vec(&encoded, &mut buffer, &ZBASE32)
+        );
+    }
+
+    #[test]
+    fn decode_ct_same_as_decode_random() {
+        use rand::prelude::*;
+
+        let mut r = rand::rngs::StdRng::from_entropy();
+        let mut buffer = Vec::new();
+        let mut buffer_ct = Vec::new();
+        for alphabet in [&ZBASE32, &RFC, &CROCKFORD, &DNS32] {
+            for _ in 0..100 {
+                let input_len = r.gen_range(0..100);
+                let input: Vec<u8> = (0..input_len).map(|_| r.gen::<u8>()).collect();
+                let encoded = encode_alphabet(&input, alphabet);
+
+                let len = decode_alphabet_vec(&encoded, &mut buffer, alphabet);
+                assert_eq!(len, decode_alphabet_vec_ct(&encoded, &mut buffer_ct, alphabet));
+                assert_eq!(buffer, buffer_ct);
+            }
+        }
+        // Aliases and case-folded variants are accepted as usual
+        assert_eq!(Ok(2), decode_alphabet_vec_ct("olIL", &mut buffer_ct, &CROCKFORD));
+        assert_eq!(decode_alphabet("0111", &CROCKFORD).unwrap(), buffer_ct);
+        assert_eq!(
+            Err(DecodeError::InvalidByte(2, b'U')),
+            decode_alphabet_vec_ct("00U0", &mut buffer_ct, &CROCKFORD)
+        );
+    }
+
+    #[test]
+    fn decode_inverted_groups() {
+        let mut out = [0_u8; 16];
+        let options = DecodeOptions::default();
+        assert_eq!(Ok(5), decode_inverted(b"em3ags7p", &mut out, &ZBASE32, &options));
+        assert_eq!(b"hello", &out[..5]);
+        assert_eq!(Ok(11), decode_inverted(b"em3ags7py376g3tprd", &mut out, &ZBASE32, &options));
+        assert_eq!(b"hello world", &out[..11]);
+        // All bits set, the trailing symbol carries the top bit of the last octet only
+        assert_eq!(Ok(6), decode_inverted(b"9999999998", &mut out, &ZBASE32, &options));
+        assert_eq!([0xff; 6], out[..6]);
+        assert_eq!(Ok(0), decode_inverted(b"", &mut out, &ZBASE32, &options));
+
+        assert_eq!(
+            Err(DecodeError::InvalidByte(3, 0xff)),
+            decode_inverted(b"em3\xffgs7p", &mut out, &ZBASE32, &options),
+        );
+        let strict = DecodeOptions { strict: true, ..Default::default() };
+        assert_eq!(Err(DecodeError::NonCanonical { index: 1 }), decode_inverted(b"y9", &mut out, &ZBASE32, &strict));
+        assert_eq!(Ok(1), decode_inverted(b"yy", &mut out, &ZBASE32, &strict));
+    }
+
+    #[test]
+    fn decode_normal_groups() {
+        let mut out = [0_u8; 16];
+        let options = DecodeOptions::default();
+        assert_eq!(Ok(5), decode_normal(b"NBSWY3DP", &mut out, &RFC, &options));
+        assert_eq!(b"hello", &out[..5]);
+        assert_eq!(Ok(11), decode_normal(b"NBSWY3DPEB3W64TMMQ", &mut out, &RFC, &options));
+        assert_eq!(b"hello world", &out[..11]);
+        assert_eq!(Ok(6), decode_normal(b"7777777777", &mut out, &RFC, &options));
+        assert_eq!([0xff; 6], out[..6]);
+        assert_eq!(Ok(0), decode_normal(b"", &mut out, &RFC, &options));
+
+        assert_eq!(
+            Err(DecodeError::InvalidByte(0, 0xff)),
+            decode_normal(b"\xffBSWY3DP", &mut out, &RFC, &options),
+        );
+        let strict = DecodeOptions { strict: true, ..Default::default() };
+        assert_eq!(Err(DecodeError::NonCanonical { index: 1 }), decode_normal(b"MF", &mut out, &RFC, &strict));
+        assert_eq!(Ok(1), decode_normal(b"ME", &mut out, &RFC, &strict));
+    }
+
+    #[test]
+    fn decode_partial_two_passes() {
+        let data: Vec<u8> = (0..22).map(|i| (i * 41 + 3) as u8).collect();
+        for alphabet in [&ZBASE32, &RFC] {
+            let encoded = encode_alphabet(&data, alphabet);
+            let mut window = [0_u8; 12];
+
+            // Two groups fit, the partial third group is left for the next pass
+            let first = decode_alphabet_slice_partial(&encoded, &mut window, alphabet).unwrap();
+            assert_eq!(DecodeProgress { written: 10, consumed: 16 }, first);
+            let mut decoded = window[..first.written].to_vec();
+
+            let second = decode_alphabet_slice_partial(&encoded[first.consumed..], &mut window, alphabet).unwrap();
+            assert_eq!(DecodeProgress { written: 12, consumed: encoded.len() - 16 }, second);
+            decoded.extend_from_slice(&window[..second.written]);
+            assert_eq!(data, decoded);
+        }
+
+        assert_eq!(
+            Err(DecodeError::OutputTooSmall { needed: 5, got: 4 }),
+            decode_alphabet_slice_partial("em3ags7py376g3tprd", &mut [0; 4], &ZBASE32)
+        );
+        // A tail shorter than a group needs only as many octets as it decodes to
+        assert_eq!(
+            Err(DecodeError::OutputTooSmall { needed: 4, got: 3 }),
+            decode_alphabet_slice_partial("em3ags7", &mut [0; 3], &ZBASE32)
+        );
+        // A short tail fits a small buffer
+        assert_eq!(
+            Ok(DecodeProgress { written: 1, consumed: 2 }),
+            decode_alphabet_slice_partial("yy", &mut [0; 1], &ZBASE32)
+        );
+        assert_eq!(
+            Err(DecodeError::InvalidLength { len: 1, remainder: 1 }),
+            decode_alphabet_slice_partial("y", &mut [0; 4], &ZBASE32)
+        );
+    }
+
+    #[test]
+    fn decode_bounded() {
+        let encoded = encode_alphabet([0_u8; 100], &RFC);
+        assert_eq!(Ok(vec![0; 100]), decode_alphabet_bounded(&encoded, &RFC, 100));
+        assert_eq!(Ok(vec![0; 100]), decode_alphabet_bounded(&encoded, &RFC, usize::MAX));
+        assert_eq!(
+            Err(DecodeError::TooLong { needed: 100, max: 99 }),
+            decode_alphabet_bounded(&encoded, &RFC, 99)
+        );
+        assert_eq!(Ok(vec![]), decode_alphabet_bounded("", &RFC, 0));
+        // The limit is checked before the symbols
+        assert_eq!(
+            Err(DecodeError::TooLong { needed: 5, max: 0 }),
+            decode_alphabet_bounded("!!!!!!!!", &RFC, 0)
+        );
+        assert_eq!(
+            Err(DecodeError::InvalidLength { len: 1, remainder: 1 }),
+            decode_alphabet_bounded("A", &RFC, 0)
+        );
+    }
+
+    #[test]
+    fn decode_vec_discards_contents() {
+        let mut buffer = b"previous contents".to_vec();
+        assert_eq!(Ok(5), decode_alphabet_vec("em3ags7p", &mut buffer, &ZBASE32));
+        assert_eq!(b"hello", &buffer[..]);
+
+        buffer.extend_from_slice(b" world");
+        assert_eq!(Err(DecodeError::InvalidByte(3, b'!')), decode_alphabet_vec("em3!gs7p", &mut buffer, &ZBASE32));
+        assert!(buffer.is_empty());
+
+        buffer.extend_from_slice(b"stale");
+        assert_eq!(
+            Err(DecodeError::InvalidLength { len: 1, remainder: 1 }),
+            decode_alphabet_vec("e", &mut buffer, &ZBASE32)
+        );
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn decode_vec_appends_at_offset() {
+        let mut buffer = b"hello ".to_vec();
+        let world = encode_alphabet("world", &RFC);
+        assert_eq!(Ok(5), decode_alphabet_vec_into_at(&world, &mut buffer, 6, &RFC));
+        assert_eq!(b"hello world", &buffer[..]);
+
+        // Data after the offset is replaced
+        assert_eq!(Ok(5), decode_alphabet_vec_into_at(&world, &mut buffer, 1, &RFC));
+        assert_eq!(b"hworld", &buffer[..]);
+
+        // Preceding data survives errors
+        assert_eq!(
+            Err(DecodeError::InvalidByte(1, b'!')),
+            decode_alphabet_vec_into_at("O!XW", &mut buffer, 6, &RFC)
+        );
+        assert_eq!(b"hworld", &buffer[..]);
+        assert_eq!(
+            Err(DecodeError::InvalidLength { len: 1, remainder: 1 }),
+            decode_alphabet_vec_into_at("O", &mut buffer, 2, &RFC)
+        );
+        assert_eq!(b"hw", &buffer[..]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn decode_vec_offset_past_end() {
+        let _ = decode_alphabet_vec_into_at("em3ags7p", &mut vec![0; 2], 3, &ZBASE32);
+    }
+
+    #[test]
+    fn decode_prefix_salvages() {
+        let data: Vec<u8> = (0..40).map(|i| (i * 37) as u8).collect();
+        for alphabet in [&ZBASE32, &RFC] {
+            let encoded = encode_alphabet(&data, alphabet).into_bytes();
+            for pos in 0..encoded.len() {
+                let mut corrupted = encoded.clone();
+                corrupted[pos] = b'!';
+                let (bytes, err) = decode_prefix(&corrupted, alphabet);
+                assert_eq!(Some(DecodeError::InvalidByte(pos, b'!')), err);
+                assert_eq!(data[..pos * 5 / 8], bytes[..]);
+            }
+            assert_eq!((data.clone(), None), decode_prefix(&encoded, alphabet));
+        }
+
+        assert_eq!((vec![], Some(DecodeError::InvalidByte(0, b'!'))), decode_prefix("!m3ags7p", &ZBASE32));
+        assert_eq!(
+            (b"hello".to_vec(), Some(DecodeError::InvalidLength { len: 9, remainder: 1 })),
+            decode_prefix("em3ags7py", &ZBASE32)
+        );
+    }
+
+    #[test]
+    fn framed_roundtrip() {
+        let data: Vec<u8> = (0..16).map(|i| (i * 37 + 1) as u8).collect();
+        for alphabet in [&ZBASE32, &RFC] {
+            for len in 0..16 {
+                let encoded = encode_framed(&data[..len], alphabet);
+                assert_eq!(encode_alphabet(&data[..len], alphabet).len() + 1, encoded.len());
+                assert_eq!(data[..len], decode_framed(&encoded, alphabet).unwrap()[..]);
+            }
+        }
+
+        assert_eq!(Err(DecodeError::InvalidLength { len: 0, remainder: 0 }), decode_framed("", &RFC));
+        assert_eq!(Ok(vec![]), decode_framed("A", &RFC));
+        // The frame symbol disagrees with the payload or is out of range
+        assert_eq!(Err(DecodeError::InvalidByte(0, b'B')), decode_framed("BNBSWY3DP", &RFC));
+        assert_eq!(Err(DecodeError::InvalidByte(0, b'Z')), decode_framed("ZNBSWY3DP", &RFC));
+        assert_eq!(Err(DecodeError::InvalidByte(0, b'!')), decode_framed("!NBSWY3DP", &RFC));
+        assert_eq!(Err(DecodeError::InvalidByte(3, b'!')), decode_framed("ANB!WY3DP", &RFC));
+    }
+
+    #[test]
+    fn decode_shortest_lengths() {
+        for alphabet in [&ZBASE32, &RFC] {
+            let sym = alphabet.encode_symbols[0];
+            assert_eq!(Ok(vec![]), decode_alphabet("", alphabet));
+            // 5 bits cannot form an octet
+            let err = DecodeError::InvalidLength { len: 1, remainder: 1 };
+            assert_eq!(Err(err.clone()), decode_alphabet([sym], alphabet));
+            assert_eq!(Err(err.clone()), decode_alphabet_slice([sym], &mut [0; 8], alphabet));
+            assert_eq!(Err(err.clone()), decode_alphabet_vec([sym], &mut Vec::new(), alphabet));
+            assert_eq!(Err(err), validate([sym], alphabet));
+            assert_eq!(Ok(vec![0]), decode_alphabet([sym, sym], alphabet));
+        }
+    }
+
+    #[test]
+    fn valid_encoded_lengths() {
+        let valid = [
+            true, false, true, false, true, true, false, true,
+            true, false, true, false, true, true, false, true,
+            true,
+        ];
+        for (len, &expected) in valid.iter().enumerate() {
+            assert_eq!(expected, is_valid_encoded_len(len), "{}", len);
+        }
+    }
+
+    #[test]
+    fn u64_roundtrip() {
+        use rand::prelude::*;
+
+        for alphabet in [&ZBASE32, &RFC] {
+            for value in [0, 1, 255, 256, 1000, 0xdead_beef, 1 << 63, u64::MAX - 1, u64::MAX] {
+                assert_eq!(Ok(value), decode_u64(encode_u64(value, alphabet), alphabet));
+            }
+        }
+        let mut r = rand::rngs::StdRng::from_entropy();
+        for _ in 0..100 {
+            let value = r.gen::<u64>() >> r.gen_range(0..64);
+            assert_eq!(Ok(value), decode_u64(encode_u64(value, &ZBASE32), &ZBASE32));
+        }
+        assert_eq!("yy", encode_u64(0, &ZBASE32));
+        assert_eq!("AE", encode_u64(1, &RFC));
+        assert_eq!(encode_alphabet(u64::MAX.to_be_bytes(), &RFC), encode_u64(u64::MAX, &RFC));
+        // Leading zero octets are optional
+        assert_eq!(Ok(1), decode_u64(encode_alphabet([0, 0, 1], &RFC), &RFC));
+        assert_eq!(Err(DecodeError::InvalidLength { len: 0, remainder: 0 }), decode_u64("", &RFC));
+        assert_eq!(
+            Err(DecodeError::TooLong { needed: 9, max: 8 }),
+            decode_u64(encode_alphabet([1; 9], &RFC), &RFC)
+        );
     }
 }
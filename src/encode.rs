@@ -1,14 +1,55 @@
 //! Base32 encoding routines
-use crate::alphabet::{Alphabet, ZBASE32, EncodeOrder};
+use crate::alphabet::{Alphabet, BitOrder, OctetOrder};
+use core::fmt;
+#[cfg(any(feature = "std", test))]
+use std::error;
+#[cfg(any(feature = "alloc", feature = "std", test))]
+use crate::alphabet::ZBASE32;
 
 #[cfg(any(feature = "alloc", feature = "std", test))]
-use std::{string::String, vec};
+use alloc::{borrow::Cow, string::String, vec, vec::Vec};
+
+/// Number of whole groups encoded at once by [encode_fmt]
+const FMT_CHUNK_GROUPS: usize = 32;
 
-///Returns encoded length for given input length
+///Returns encoded length for given input length, which is exactly the number of
+///symbols written by [encode_alphabet_slice] without padding.
+///Returns `None` if it does not fit in `usize`, which requires inputs of more than 5/8
+///of the address space and is only reachable in practice on 32-bit targets;
+///the allocating encoders panic in that case.
 pub fn encoded_len(bytes_len: usize) -> Option<usize> {
     let min_bytes = bytes_len / 5;
     let rem = bytes_len % 5;
-    min_bytes.checked_mul(8).and_then(|c| c.checked_add(rem * 2 + 1))
+    min_bytes.checked_mul(8).and_then(|c| c.checked_add((rem * 8).div_ceil(5)))
+}
+
+/// Potential encoded length errors
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LengthError {
+    /// The encoded length does not fit in `usize`.
+    Overflow,
+    /// The input encodes to fewer symbols than requested. Both lengths are provided.
+    InputTooShort { requested: usize, available: usize },
+}
+
+impl fmt::Display for LengthError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LengthError::Overflow => write!(f, "Encoded length overflows usize"),
+            LengthError::InputTooShort { requested, available } => {
+                write!(f, "Input is too short: requested {} symbols, available {}", requested, available)
+            }
+        }
+    }
+}
+
+#[cfg(any(feature = "std", test))]
+impl error::Error for LengthError {}
+
+///Returns encoded length for given input length as [encoded_len] does,
+///with a typed error instead of `None`.
+pub fn encoded_len_checked(bytes_len: usize) -> Result<usize, LengthError> {
+    encoded_len(bytes_len).ok_or(LengthError::Overflow)
 }
 
 ///Encode base32 using the specified [Alphabet] and the predefined output slice.
@@ -18,128 +59,323 @@ pub fn encode_alphabet_slice<T: AsRef<[u8]>>(
     output_buf: &mut [u8],
     alphabet: &Alphabet,
 ) -> usize {
-    let encode_table = alphabet.encode_symbols;
+    encode_slice_impl(input.as_ref(), output_buf, alphabet, false)
+}
+
+/// Longest input encoded by [encode_small] itself
+const SMALL_INPUT_MAX: usize = 40;
+
+///Encode base32 using the specified [Alphabet] and the predefined output slice,
+///optimized for short inputs of up to 40 bytes such as hashes and keys.
+///Returns a `usize` of how many output bytes are filled.
+///
+///Whole 5-byte groups are encoded as one 40-bit word each rather than byte by byte,
+///longer inputs are passed to [encode_alphabet_slice]. The output is identical to
+///[encode_alphabet_slice].
+///
+///# Example
+///
+///```rust
+///extern crate rspamd_base32;
+///
+///fn main() {
+///    let mut out = [0_u8; 8];
+///    let len = rspamd_base32::encode_small(b"hello", &mut out, &rspamd_base32::alphabet::ZBASE32);
+///    assert_eq!(b"em3ags7p", &out[..len]);
+///}
+///```
+pub fn encode_small<T: AsRef<[u8]>>(
+    input: T,
+    output_buf: &mut [u8],
+    alphabet: &Alphabet,
+) -> usize {
     let input_bytes = input.as_ref();
+    if input_bytes.len() > SMALL_INPUT_MAX {
+        return encode_alphabet_slice(input_bytes, output_buf, alphabet);
+    }
+
+    let groups = input_bytes.len() / 5;
+    let (head, tail) = input_bytes.split_at(groups * 5);
+    let (head_out, tail_out) = output_buf.split_at_mut(groups * 8);
+
+    for (chunk, out) in head.chunks_exact(5).zip(head_out.chunks_exact_mut(8)) {
+        let mut octets = [chunk[0], chunk[1], chunk[2], chunk[3], chunk[4]];
+        if alphabet.octet_order == OctetOrder::Reversed {
+            octets.reverse();
+        }
+        let [b0, b1, b2, b3, b4] = octets;
+
+        match alphabet.bit_order {
+            BitOrder::MsbFirst => {
+                let bits = u64::from_be_bytes([0, 0, 0, b0, b1, b2, b3, b4]);
+                for (k, sym) in out.iter_mut().enumerate() {
+                    *sym = alphabet.encode_symbols[(bits >> (35 - 5 * k)) as usize & 0x1F];
+                }
+            }
+            BitOrder::LsbFirst => {
+                let bits = u64::from_le_bytes([b0, b1, b2, b3, b4, 0, 0, 0]);
+                for (k, sym) in out.iter_mut().enumerate() {
+                    *sym = alphabet.encode_symbols[(bits >> (5 * k)) as usize & 0x1F];
+                }
+            }
+        }
+    }
+
+    groups * 8 + encode_slice_impl(tail, tail_out, alphabet, false)
+}
+
+///Encode base32 using the specified [Alphabet] and the predefined output slice,
+///looking symbols up without secret-dependent memory accesses.
+///Returns a `usize` of how many output bytes are filled.
+///
+///Each symbol is selected by scanning all 32 entries of the encoding table with a mask,
+///so the accessed addresses do not depend on the input. This makes encoding several times
+///slower than [encode_alphabet_slice] and is meant for secret material such as keys.
+///The output is identical to [encode_alphabet_slice].
+pub fn encode_alphabet_slice_ct<T: AsRef<[u8]>>(
+    input: T,
+    output_buf: &mut [u8],
+    alphabet: &Alphabet,
+) -> usize {
+    encode_slice_impl(input.as_ref(), output_buf, alphabet, true)
+}
+
+/// Returns the symbol for a 5-bit `value`, scanning the whole table if `const_time` is set
+#[inline(always)]
+const fn symbol(encode_table: &[u8; 32], value: usize, const_time: bool) -> u8 {
+    if !const_time {
+        return encode_table[value];
+    }
+
+    let mut result = 0_u8;
+    let mut j = 0;
+    while j < encode_table.len() {
+        // 0xFF when j == value, 0 otherwise: `diff - 1` only borrows from the high bits for zero
+        let diff = (j ^ value) as u32;
+        let mask = (diff.wrapping_sub(1) >> 8) as u8;
+        result |= encode_table[j] & mask;
+        j += 1;
+    }
+    result
+}
+
+/// Input position of the `i`-th packed octet out of `len`, octets of every group
+/// are taken last to first for [OctetOrder::Reversed]
+#[inline(always)]
+const fn octet_index(i: usize, len: usize, octet_order: OctetOrder) -> usize {
+    match octet_order {
+        OctetOrder::Natural => i,
+        OctetOrder::Reversed => {
+            let start = i - i % 5;
+            let group = if len - start < 5 { len - start } else { 5 };
+            start + group - 1 - i % 5
+        }
+    }
+}
+
+/// Encoding core, const so that it can be used by [encode_const]
+const fn encode_slice_impl(
+    input_bytes: &[u8],
+    output_buf: &mut [u8],
+    alphabet: &Alphabet,
+    const_time: bool,
+) -> usize {
+    let encode_table = alphabet.encode_symbols;
     let mut remain = -1_i32;
     let mut o = 0_usize;
+    let mut i = 0_usize;
 
-    if alphabet.encode_order == EncodeOrder::OrderInversed {
-        for i in 0..input_bytes.len() {
+    if matches!(alphabet.bit_order, BitOrder::LsbFirst) {
+        while i < input_bytes.len() {
+            let byte = input_bytes[octet_index(i, input_bytes.len(), alphabet.octet_order)];
             remain = match i % 5 {
                 0 => {
                     // 8 bits of input and 3 to remain
-                    let x = input_bytes[i] as i32;
-                    output_buf[o] = encode_table[(x & 0x1F) as usize];
-                    o = o + 1;
+                    let x = byte as i32;
+                    output_buf[o] = symbol(&encode_table, (x & 0x1F) as usize, const_time);
+                    o += 1;
                     x >> 5
                 },
                 1 => {
                     // 11 bits of input, 1 to remain
-                    let inp = input_bytes[i] as i32;
+                    let inp = byte as i32;
                     let x = remain | inp << 3;
-                    output_buf[o] = encode_table[(x & 0x1F) as usize];
-                    o = o + 1;
-                    output_buf[o] = encode_table[(x >> 5 & 0x1F) as usize];
-                    o = o + 1;
+                    output_buf[o] = symbol(&encode_table, (x & 0x1F) as usize, const_time);
+                    o += 1;
+                    output_buf[o] = symbol(&encode_table, (x >> 5 & 0x1F) as usize, const_time);
+                    o += 1;
                     x >> 10
                 }
                 2 => {
                     // 9 bits of input, 4 to remain
-                    let inp = input_bytes[i] as i32;
+                    let inp = byte as i32;
                     let x = remain | inp << 1;
-                    output_buf[o] = encode_table[(x & 0x1F) as usize];
-                    o = o + 1;
+                    output_buf[o] = symbol(&encode_table, (x & 0x1F) as usize, const_time);
+                    o += 1;
                     x >> 5
                 },
                 3 => {
                     // 12 bits of input, 2 to remain
-                    let inp = input_bytes[i] as i32;
+                    let inp = byte as i32;
                     let x = remain | inp << 4;
-                    output_buf[o] = encode_table[(x & 0x1F) as usize];
-                    o = o + 1;
-                    output_buf[o] = encode_table[(x >> 5 & 0x1F) as usize];
-                    o = o + 1;
+                    output_buf[o] = symbol(&encode_table, (x & 0x1F) as usize, const_time);
+                    o += 1;
+                    output_buf[o] = symbol(&encode_table, (x >> 5 & 0x1F) as usize, const_time);
+                    o += 1;
                     x >> 10 & 0x3
                 },
                 4 => {
                     // 10 bits of output, nothing to remain
-                    let inp = input_bytes[i] as i32;
+                    let inp = byte as i32;
                     let x = remain | inp << 2;
-                    output_buf[o] = encode_table[(x & 0x1F) as usize];
-                    o = o + 1;
-                    output_buf[o] = encode_table[(x >> 5 & 0x1F) as usize];
-                    o = o + 1;
+                    output_buf[o] = symbol(&encode_table, (x & 0x1F) as usize, const_time);
+                    o += 1;
+                    output_buf[o] = symbol(&encode_table, (x >> 5 & 0x1F) as usize, const_time);
+                    o += 1;
                     -1
                 },
-                _ => unreachable!("Impossible remainder"),
+                _ => panic!("Impossible remainder"),
             };
+            i += 1;
         }
     }
     else {
-        for i in 0..input_bytes.len() {
+        while i < input_bytes.len() {
+            let byte = input_bytes[octet_index(i, input_bytes.len(), alphabet.octet_order)];
             remain = match i % 5 {
                 0 => {
                     // 8 bits of input and 3 to remain
-                    let inp = input_bytes[i] as i32;
+                    let inp = byte as i32;
                     let x = inp >> 3;
-                    output_buf[o] = encode_table[(x & 0x1F) as usize];
-                    o = o + 1;
+                    output_buf[o] = symbol(&encode_table, (x & 0x1F) as usize, const_time);
+                    o += 1;
                     (inp & 7) << 2
                 },
                 1 => {
                     // 11 bits of input, 1 to remain
-                    let inp = input_bytes[i] as i32;
+                    let inp = byte as i32;
                     let x = (remain << 6) | inp;
-                    output_buf[o] = encode_table[(x >> 6 & 0x1F) as usize];
-                    o = o + 1;
-                    output_buf[o] = encode_table[(x >> 1 & 0x1F) as usize];
-                    o = o + 1;
+                    output_buf[o] = symbol(&encode_table, (x >> 6 & 0x1F) as usize, const_time);
+                    o += 1;
+                    output_buf[o] = symbol(&encode_table, (x >> 1 & 0x1F) as usize, const_time);
+                    o += 1;
                     (x & 0x1) << 4
                 }
                 2 => {
                     // 9 bits of input, 4 to remain
-                    let inp = input_bytes[i] as i32;
+                    let inp = byte as i32;
                     let x = (remain << 4) | inp;
-                    output_buf[o] = encode_table[(x >> 4 & 0x1F) as usize];
-                    o = o + 1;
+                    output_buf[o] = symbol(&encode_table, (x >> 4 & 0x1F) as usize, const_time);
+                    o += 1;
                     (x & 15) << 1
                 },
                 3 => {
                     // 12 bits of input, 2 to remain\
-                    let inp = input_bytes[i] as i32;
+                    let inp = byte as i32;
                     let x = remain << 7 | inp;
-                    output_buf[o] = encode_table[(x >> 7 & 0x1F) as usize];
-                    o = o + 1;
-                    output_buf[o] = encode_table[(x >> 2 & 0x1F) as usize];
-                    o = o + 1;
+                    output_buf[o] = symbol(&encode_table, (x >> 7 & 0x1F) as usize, const_time);
+                    o += 1;
+                    output_buf[o] = symbol(&encode_table, (x >> 2 & 0x1F) as usize, const_time);
+                    o += 1;
                     (x & 3) << 3
                 },
                 4 => {
                     // 10 bits of output, nothing to remain
-                    let inp = input_bytes[i] as i32;
+                    let inp = byte as i32;
                     let x = remain << 5 | inp;
-                    output_buf[o] = encode_table[(x >> 5 & 0x1F) as usize];
-                    o = o + 1;
-                    output_buf[o] = encode_table[(x & 0x1F) as usize];
-                    o = o + 1;
+                    output_buf[o] = symbol(&encode_table, (x >> 5 & 0x1F) as usize, const_time);
+                    o += 1;
+                    output_buf[o] = symbol(&encode_table, (x & 0x1F) as usize, const_time);
+                    o += 1;
                     -1
                 },
-                _ => unreachable!("Impossible remainder"),
+                _ => panic!("Impossible remainder"),
             };
+            i += 1;
         }
     }
 
     if remain >= 0 {
-        output_buf[o] = encode_table[(remain & 0x1F) as usize];
-        o = o + 1;
+        output_buf[o] = symbol(&encode_table, (remain & 0x1F) as usize, const_time);
+        o += 1;
     }
 
     o
 }
 
+/// Exact number of symbols produced for `bytes_len` octets, may overflow
+const fn exact_encoded_len(bytes_len: usize) -> usize {
+    bytes_len / 5 * 8 + (bytes_len % 5 * 8).div_ceil(5)
+}
+
+///Checks in constant time that `candidate_encoded` is the encoding of `expected_decoded`
+///using the specified [Alphabet], e.g. to verify tokens derived from a secret.
+///
+///The expected symbols are produced lazily with [encode_alphabet_slice_ct] and every symbol
+///of the candidate is compared without early exit, so timing depends only on the lengths.
+///Symbols are compared exactly: case-folded variants and aliases do not match.
+///
+///# Example
+///
+///```rust
+///extern crate rspamd_base32;
+///
+///fn main() {
+///    let alphabet = &rspamd_base32::alphabet::ZBASE32;
+///    assert!(rspamd_base32::matches_encoding("em3ags7p", b"hello", alphabet));
+///    assert!(!rspamd_base32::matches_encoding("em3ags7y", b"hello", alphabet));
+///}
+///```
+pub fn matches_encoding(candidate_encoded: &str, expected_decoded: &[u8], alphabet: &Alphabet) -> bool {
+    let candidate = candidate_encoded.as_bytes();
+    let mut diff = (candidate.len() != exact_encoded_len(expected_decoded.len())) as u8;
+    let mut groups = expected_decoded.chunks(5);
+    let mut symbols = [0_u8; 8];
+    let mut len = 0;
+
+    for (i, &c) in candidate.iter().enumerate() {
+        if i % 8 == 0 {
+            len = groups.next().map_or(0, |group| encode_alphabet_slice_ct(group, &mut symbols, alphabet));
+        }
+        // Positions past the expected encoding are compared against zero, which no symbol is
+        let sym = if i % 8 < len { symbols[i % 8] } else { 0 };
+        diff |= c ^ sym;
+    }
+
+    diff == 0
+}
+
+///Encode base32 using the specified [Alphabet] at compile time.
+///`M` must be the exact encoded length of `N` bytes, otherwise evaluation panics.
+///
+///# Example
+///
+///```rust
+///use rspamd_base32::alphabet::ZBASE32;
+///use rspamd_base32::encode::encode_const;
+///
+///const ENCODED: [u8; 8] = encode_const(b"hello", &ZBASE32);
+///
+///fn main() {
+///    assert_eq!(b"em3ags7p", &ENCODED);
+///}
+///```
+pub const fn encode_const<const N: usize, const M: usize>(input: &[u8; N], alphabet: &Alphabet) -> [u8; M] {
+    let mut output = [0_u8; M];
+    // Checked upfront, so a short output reports the length mismatch rather than an index panic
+    assert!(exact_encoded_len(N) == M, "M must be the exact encoded length of N bytes");
+    encode_slice_impl(input, &mut output, alphabet, false);
+    output
+}
+
 ///Encode base32 using the specified [Alphabet].
 ///Returns a `String`.
 ///
+///# Panics
+///
+///Panics if the encoded length overflows `usize`, as it always has: the signature is kept
+///for compatibility, [try_encode_alphabet] returns [LengthError::Overflow] instead.
+///
 ///# Example
 ///
 ///```rust
@@ -156,16 +392,632 @@ pub fn encode_alphabet_slice<T: AsRef<[u8]>>(
 ///```
 #[cfg(any(feature = "alloc", feature = "std", test))]
 pub fn encode_alphabet<T: AsRef<[u8]>>(input: T, alphabet: &Alphabet) -> String {
-    let encoded_size = encoded_len(input.as_ref().len())
-        .expect("usize overflow when calculating buffer size");
+    try_encode_alphabet(input, alphabet).expect(LENGTH_OVERFLOW)
+}
+
+/// Panic message of the allocating encoders that predate [encoded_len_checked]
+#[cfg(any(feature = "alloc", feature = "std", test))]
+const LENGTH_OVERFLOW: &str = "usize overflow when calculating buffer size";
+
+/// Buffer size for encoding `bytes_len` octets, panics with [LENGTH_OVERFLOW] on overflow
+#[cfg(any(feature = "alloc", feature = "std", test))]
+fn encoded_buffer_len(bytes_len: usize) -> usize {
+    encoded_len_checked(bytes_len).expect(LENGTH_OVERFLOW)
+}
+
+///Encode base32 using the specified [Alphabet] as [encode_alphabet] does,
+///returning [LengthError::Overflow] instead of panicking if the encoding cannot fit in memory.
+///Returns a `Result` containing a `String`.
+///
+///# Example
+///
+///```rust
+///extern crate rspamd_base32;
+///
+///fn main() {
+///    let encoded = rspamd_base32::try_encode_alphabet("hello", &rspamd_base32::alphabet::RFC);
+///    assert_eq!(Ok("NBSWY3DP".to_owned()), encoded);
+///}
+///```
+#[cfg(any(feature = "alloc", feature = "std", test))]
+pub fn try_encode_alphabet<T: AsRef<[u8]>>(input: T, alphabet: &Alphabet) -> Result<String, LengthError> {
+    let encoded_size = encoded_len_checked(input.as_ref().len())?;
     let mut buf = vec![0; encoded_size];
     let enc_len = encode_alphabet_slice(input, &mut buf[..], alphabet);
-    String::from_utf8(buf[0..enc_len].to_owned()).expect("Invalid UTF8")
+    buf.truncate(enc_len);
+    Ok(String::from_utf8(buf).expect(ASCII_SYMBOLS))
+}
+
+/// Every output byte comes from the 32 symbols of an [Alphabet], which are printable ASCII:
+/// built-in alphabets are ASCII literals and [crate::alphabet::AlphabetBuilder] rejects
+/// anything outside `[32, 126]`. `#![forbid(unsafe_code)]` rules out unchecked conversions,
+/// so the output is validated exactly once, on the final buffer, and that cannot fail.
+#[cfg(any(feature = "alloc", feature = "std", feature = "heapless", test))]
+const ASCII_SYMBOLS: &str = "alphabet symbols are ASCII";
+
+///Incremental encoder for input that arrives in arbitrary fragments.
+///Up to 4 octets of an incomplete group are kept between [EncoderState::feed] calls,
+///so the concatenated output equals [encode_alphabet] of the concatenated input.
+///
+///# Example
+///
+///```rust
+///use rspamd_base32::alphabet::ZBASE32;
+///use rspamd_base32::encode::EncoderState;
+///
+///fn main() {
+///    let mut state = EncoderState::new(&ZBASE32);
+///    let mut out = String::new();
+///    state.feed(b"hel", &mut out);
+///    state.feed(b"lo", &mut out);
+///    state.finish(&mut out);
+///    assert_eq!("em3ags7p", out);
+///}
+///```
+#[cfg(any(feature = "alloc", feature = "std", test))]
+#[derive(Clone, Debug)]
+pub struct EncoderState<'a> {
+    alphabet: &'a Alphabet,
+    pending: [u8; 5],
+    pending_len: usize,
+}
+
+#[cfg(any(feature = "alloc", feature = "std", test))]
+impl<'a> EncoderState<'a> {
+    /// Creates an encoder for the specified [Alphabet]
+    pub fn new(alphabet: &'a Alphabet) -> Self {
+        EncoderState { alphabet, pending: [0; 5], pending_len: 0 }
+    }
+
+    /// Encodes complete groups of a fragment appending symbols to `out`
+    pub fn feed(&mut self, chunk: &[u8], out: &mut String) {
+        out.reserve((self.pending_len + chunk.len()) / 5 * 8);
+
+        for &byte in chunk {
+            self.pending[self.pending_len] = byte;
+            self.pending_len += 1;
+
+            if self.pending_len == self.pending.len() {
+                self.flush_pending(out);
+            }
+        }
+    }
+
+    /// Encodes the trailing partial group, the state can be reused afterwards
+    pub fn finish(&mut self, out: &mut String) {
+        self.flush_pending(out);
+    }
+
+    fn flush_pending(&mut self, out: &mut String) {
+        let mut symbols = [0_u8; 8];
+        let len = encode_alphabet_slice(&self.pending[..self.pending_len], &mut symbols, self.alphabet);
+        out.extend(symbols[..len].iter().map(|&sym| sym as char));
+        self.pending_len = 0;
+    }
+}
+
+///Iterator over encoded symbols produced by [encode_iter]
+#[derive(Clone, Debug)]
+pub struct EncodeIter<'a, I> {
+    input: I,
+    alphabet: &'a Alphabet,
+    symbols: [u8; 8],
+    pos: usize,
+    len: usize,
+}
+
+impl<I: Iterator<Item = u8>> Iterator for EncodeIter<'_, I> {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        if self.pos == self.len {
+            // Encode the next group of up to 5 octets
+            let mut group = [0_u8; 5];
+            let mut group_len = 0;
+            while group_len < group.len() {
+                match self.input.next() {
+                    Some(byte) => {
+                        group[group_len] = byte;
+                        group_len += 1;
+                    }
+                    None => break,
+                }
+            }
+
+            self.len = encode_alphabet_slice(&group[..group_len], &mut self.symbols, self.alphabet);
+            self.pos = 0;
+
+            if self.len == 0 {
+                return None;
+            }
+        }
+
+        let sym = self.symbols[self.pos];
+        self.pos += 1;
+        Some(sym)
+    }
+}
+
+///Encode base32 lazily using the specified [Alphabet].
+///Returns an iterator over encoded symbols, no output buffer is allocated.
+///
+///# Example
+///
+///```rust
+///extern crate rspamd_base32;
+///
+///fn main() {
+///    let encoded: String = rspamd_base32::encode_iter(
+///        b"hello".iter().copied(),
+///        &rspamd_base32::alphabet::ZBASE32,
+///    ).map(char::from).collect();
+///    assert_eq!("em3ags7p", encoded);
+///}
+///```
+pub fn encode_iter<I: IntoIterator<Item = u8>>(input: I, alphabet: &Alphabet) -> EncodeIter<'_, I::IntoIter> {
+    EncodeIter {
+        input: input.into_iter(),
+        alphabet,
+        symbols: [0; 8],
+        pos: 0,
+        len: 0,
+    }
+}
+
+///Encode base32 lazily using the specified [Alphabet].
+///Returns an iterator over encoded symbols as `char`, e.g. to extend a fixed capacity
+///string without `alloc`.
+///
+///# Example
+///
+///```rust
+///extern crate rspamd_base32;
+///
+///fn main() {
+///    let mut encoded = String::new();
+///    encoded.extend(rspamd_base32::encode_chars(b"hello", &rspamd_base32::alphabet::ZBASE32));
+///    assert_eq!("em3ags7p", encoded);
+///}
+///```
+pub fn encode_chars<'a>(input: &'a [u8], alphabet: &'a Alphabet) -> impl Iterator<Item = char> + 'a {
+    encode_iter(input.iter().copied(), alphabet).map(char::from)
+}
+
+///Encode base32 using the specified [Alphabet] directly into a [fmt::Write] sink,
+///such as a `String` or a `fmt::Formatter`, without an intermediate allocation.
+///
+///# Example
+///
+///```rust
+///extern crate rspamd_base32;
+///
+///fn main() {
+///    let mut out = String::from("key=");
+///    rspamd_base32::encode_fmt(b"hello", &rspamd_base32::alphabet::ZBASE32, &mut out).unwrap();
+///    assert_eq!("key=em3ags7p", out);
+///}
+///```
+pub fn encode_fmt<W: fmt::Write>(input: &[u8], alphabet: &Alphabet, out: &mut W) -> fmt::Result {
+    let mut symbols = [0_u8; FMT_CHUNK_GROUPS * 8];
+
+    for chunk in input.chunks(FMT_CHUNK_GROUPS * 5) {
+        let len = encode_alphabet_slice(chunk, &mut symbols, alphabet);
+        out.write_str(core::str::from_utf8(&symbols[..len]).map_err(|_| fmt::Error)?)?;
+    }
+
+    Ok(())
+}
+
+///Encode base32 using the specified [Alphabet].
+///Returns a borrowed empty string for empty input, so nothing is allocated,
+///and an owned `String` otherwise.
+///
+///# Example
+///
+///```rust
+///extern crate rspamd_base32;
+///use std::borrow::Cow;
+///
+///fn main() {
+///    let alphabet = &rspamd_base32::alphabet::ZBASE32;
+///    assert!(matches!(rspamd_base32::encode_cow("", alphabet), Cow::Borrowed("")));
+///    assert_eq!("em3ags7p", rspamd_base32::encode_cow("hello", alphabet));
+///}
+///```
+#[cfg(any(feature = "alloc", feature = "std", test))]
+pub fn encode_cow<T: AsRef<[u8]>>(input: T, alphabet: &Alphabet) -> Cow<'static, str> {
+    if input.as_ref().is_empty() {
+        Cow::Borrowed("")
+    } else {
+        Cow::Owned(encode_alphabet(input, alphabet))
+    }
+}
+
+///Encode each of `inputs` using the specified [Alphabet].
+///Returns a `Vec` of encoded strings in the input order.
+#[cfg(any(feature = "alloc", feature = "std", test))]
+pub fn encode_many<'a, I: IntoIterator<Item = &'a [u8]>>(inputs: I, alphabet: &Alphabet) -> Vec<String> {
+    inputs.into_iter().map(|input| encode_alphabet(input, alphabet)).collect()
+}
+
+///Encode base32 using the specified [Alphabet] into a caller-owned `scratch` buffer,
+///which is cleared first, so encoding many values reuses one allocation.
+///Returns the encoded string borrowed from `scratch`: it must be used or copied
+///before `scratch` is reused for the next input.
+///
+///# Example
+///
+///```rust
+///extern crate rspamd_base32;
+///
+///fn main() {
+///    let mut scratch = Vec::new();
+///    for (input, expected) in [("hello", "em3ags7p"), ("test123", "wm3g84fg13cy")] {
+///        let encoded = rspamd_base32::encode_into_reused(
+///            input,
+///            &mut scratch,
+///            &rspamd_base32::alphabet::ZBASE32,
+///        );
+///        assert_eq!(expected, encoded);
+///    }
+///}
+///```
+#[cfg(any(feature = "alloc", feature = "std", test))]
+pub fn encode_into_reused<'s, T: AsRef<[u8]>>(input: T, scratch: &'s mut Vec<u8>, alphabet: &Alphabet) -> &'s str {
+    let encoded_size = encoded_buffer_len(input.as_ref().len());
+    scratch.clear();
+    scratch.resize(encoded_size, 0);
+    let enc_len = encode_alphabet_slice(input, &mut scratch[..], alphabet);
+    scratch.truncate(enc_len);
+    core::str::from_utf8(scratch).expect(ASCII_SYMBOLS)
+}
+
+///Reusable output buffer for encoding many inputs without allocating on every call.
+///The buffer only grows, so in steady state [EncodeBuffer::encode] does not allocate.
+///
+///# Example
+///
+///```rust
+///use rspamd_base32::alphabet::ZBASE32;
+///use rspamd_base32::encode::EncodeBuffer;
+///
+///fn main() {
+///    let mut buffer = EncodeBuffer::new();
+///    assert_eq!("em3ags7p", buffer.encode(b"hello", &ZBASE32));
+///    assert_eq!("wm3g84fg13cy", buffer.encode(b"test123", &ZBASE32));
+///}
+///```
+#[cfg(any(feature = "alloc", feature = "std", test))]
+#[derive(Clone, Debug, Default)]
+pub struct EncodeBuffer {
+    buf: Vec<u8>,
+}
+
+#[cfg(any(feature = "alloc", feature = "std", test))]
+impl EncodeBuffer {
+    /// Creates an empty buffer, the first call to [EncodeBuffer::encode] allocates
+    pub fn new() -> Self {
+        EncodeBuffer { buf: Vec::new() }
+    }
+
+    /// Creates a buffer that can hold the encoding of `bytes_len` octets without growing
+    pub fn with_capacity(bytes_len: usize) -> Self {
+        let encoded_size = encoded_buffer_len(bytes_len);
+        EncodeBuffer { buf: Vec::with_capacity(encoded_size) }
+    }
+
+    /// Encodes `input` with the specified [Alphabet], replacing the previous output
+    pub fn encode<'a>(&'a mut self, input: &[u8], alphabet: &Alphabet) -> &'a str {
+        encode_into_reused(input, &mut self.buf, alphabet)
+    }
+}
+
+///Encode base32 using the specified [Alphabet], inserting `sep` after every `group` symbols
+///to make long values easier to read and transcribe. `group == 0` disables grouping.
+///Returns a `Result` containing a `String`, or an error if `sep` is not printable ASCII
+///or is accepted by the decoder as a symbol, which would break the roundtrip.
+///
+///# Example
+///
+///```rust
+///extern crate rspamd_base32;
+///
+///fn main() {
+///    let encoded = rspamd_base32::encode_grouped(
+///        b"hello",
+///        &rspamd_base32::alphabet::ZBASE32,
+///        4,
+///        b'-',
+///    );
+///    assert_eq!(Ok("em3a-gs7p".to_owned()), encoded);
+///}
+///```
+#[cfg(any(feature = "alloc", feature = "std", test))]
+pub fn encode_grouped(
+    input: &[u8],
+    alphabet: &Alphabet,
+    group: usize,
+    sep: u8,
+) -> Result<String, crate::alphabet::ParseAlphabetError> {
+    if !(b' '..=b'~').contains(&sep) {
+        return Err(crate::alphabet::ParseAlphabetError::UnprintableByte(sep));
+    }
+    if alphabet.contains(sep) {
+        return Err(crate::alphabet::ParseAlphabetError::SeparatorCollision(sep));
+    }
+
+    let encoded_size = encoded_buffer_len(input.len());
+    let separators = encoded_size.checked_div(group).unwrap_or(0);
+    let mut out = String::with_capacity(encoded_size + separators);
+
+    for (i, sym) in encode_iter(input.iter().copied(), alphabet).enumerate() {
+        if group != 0 && i != 0 && i % group == 0 {
+            out.push(sep as char);
+        }
+        out.push(sym as char);
+    }
+
+    Ok(out)
+}
+
+///Encode base32 using the specified [Alphabet], inserting `line_ending` after every `width`
+///symbols, e.g. for PEM-like blocks. `width == 0` disables wrapping and the last line is
+///not terminated. Returns a `String` that [crate::decode::decode_alphabet_lenient] decodes.
+///
+///# Example
+///
+///```rust
+///extern crate rspamd_base32;
+///
+///fn main() {
+///    let encoded = rspamd_base32::encode_wrapped(
+///        b"hello",
+///        &rspamd_base32::alphabet::ZBASE32,
+///        4,
+///        "\n",
+///    );
+///    assert_eq!("em3a\ngs7p", encoded);
+///}
+///```
+#[cfg(any(feature = "alloc", feature = "std", test))]
+pub fn encode_wrapped(input: &[u8], alphabet: &Alphabet, width: usize, line_ending: &str) -> String {
+    let encoded_size = encoded_buffer_len(input.len());
+    let line_endings = encoded_size.saturating_sub(1).checked_div(width).unwrap_or(0);
+    let mut out = String::with_capacity(encoded_size + line_endings * line_ending.len());
+
+    for (i, sym) in encode_iter(input.iter().copied(), alphabet).enumerate() {
+        if width != 0 && i != 0 && i % width == 0 {
+            out.push_str(line_ending);
+        }
+        out.push(sym as char);
+    }
+
+    out
+}
+
+///Encode base32 using the specified [Alphabet], prefixed with a symbol encoding
+///`input.len() % 5`, the number of octets in the trailing partial group.
+///Returns a `String` that [crate::decode::decode_framed] reconstructs exactly.
+///
+///# Example
+///
+///```rust
+///extern crate rspamd_base32;
+///
+///fn main() {
+///    let alphabet = &rspamd_base32::alphabet::ZBASE32;
+///    let encoded = rspamd_base32::encode_framed(b"hello!", alphabet);
+///    assert_eq!(b"hello!", &rspamd_base32::decode_framed(&encoded, alphabet).unwrap()[..]);
+///}
+///```
+#[cfg(any(feature = "alloc", feature = "std", test))]
+pub fn encode_framed<T: AsRef<[u8]>>(input: T, alphabet: &Alphabet) -> String {
+    let input_bytes = input.as_ref();
+    let mut encoded = String::with_capacity(1 + encoded_len(input_bytes.len()).unwrap_or(0));
+    encoded.push(alphabet.encode_symbols[input_bytes.len() % 5] as char);
+    encode_fmt(input_bytes, alphabet, &mut encoded).expect("writing to String cannot fail");
+    encoded
+}
+
+///Encode a `u64` using the specified [Alphabet] as its big-endian octets
+///with leading zero octets trimmed, `0` is encoded as a single zero octet.
+///Returns a short `String` suitable for IDs, see [crate::decode::decode_u64].
+///
+///# Example
+///
+///```rust
+///extern crate rspamd_base32;
+///
+///fn main() {
+///    let alphabet = &rspamd_base32::alphabet::ZBASE32;
+///    let encoded = rspamd_base32::encode_u64(1000, alphabet);
+///    assert_eq!(Ok(1000), rspamd_base32::decode_u64(&encoded, alphabet));
+///}
+///```
+#[cfg(any(feature = "alloc", feature = "std", test))]
+pub fn encode_u64(value: u64, alphabet: &Alphabet) -> String {
+    let bytes = value.to_be_bytes();
+    let skip = (value.leading_zeros() / 8).min(7) as usize;
+    encode_alphabet(&bytes[skip..], alphabet)
+}
+
+///Encode the leading octets of `input` using the specified [Alphabet] and keep exactly
+///`symbols` symbols, e.g. for fixed-length identifiers derived from a hash.
+///Returns a `String` equal to the first `symbols` characters of [encode_alphabet], or
+///[LengthError::InputTooShort] if `symbols` exceeds the exact encoded length of `input`,
+///which is `input.len() * 8 / 5` rounded up: the output is never padded.
+///
+///Only the whole groups needed for the prefix are encoded, so the last symbol carries
+///the following input bits rather than zero padding.
+///
+///# Example
+///
+///```rust
+///extern crate rspamd_base32;
+///
+///fn main() {
+///    let alphabet = &rspamd_base32::alphabet::ZBASE32;
+///    assert_eq!(Ok("em3ag".to_owned()), rspamd_base32::encode_truncated(b"hello", alphabet, 5));
+///}
+///```
+#[cfg(any(feature = "alloc", feature = "std", test))]
+pub fn encode_truncated(input: &[u8], alphabet: &Alphabet, symbols: usize) -> Result<String, LengthError> {
+    let available = exact_encoded_len(input.len());
+    if symbols > available {
+        return Err(LengthError::InputTooShort { requested: symbols, available });
+    }
+
+    let groups = symbols.div_ceil(8);
+    let mut encoded = encode_alphabet(&input[..input.len().min(groups * 5)], alphabet);
+    encoded.truncate(symbols);
+    Ok(encoded)
+}
+
+///Encode the 16 octets of a UUID using the specified [Alphabet].
+///Returns a 26 symbol `String`, see [crate::decode::decode_uuid].
+///
+///# Example
+///
+///```rust
+///extern crate rspamd_base32;
+///
+///fn main() {
+///    let encoded = rspamd_base32::encode_uuid(&uuid::Uuid::nil(), &rspamd_base32::alphabet::ZBASE32);
+///    assert_eq!("yyyyyyyyyyyyyyyyyyyyyyyyyy", encoded);
+///}
+///```
+#[cfg(feature = "uuid")]
+pub fn encode_uuid(u: &uuid::Uuid, alphabet: &Alphabet) -> String {
+    encode_alphabet(u.as_bytes(), alphabet)
+}
+
+///Encode the 16 octets of an IPv6 address using the specified [Alphabet], e.g. as a
+///compact DNS label. Returns a 26 symbol `String`, see [crate::decode::decode_ipv6].
+///
+///# Example
+///
+///```rust
+///extern crate rspamd_base32;
+///
+///fn main() {
+///    let alphabet = &rspamd_base32::alphabet::ZBASE32;
+///    let encoded = rspamd_base32::encode_ipv6(std::net::Ipv6Addr::LOCALHOST, alphabet);
+///    assert_eq!(Ok(std::net::Ipv6Addr::LOCALHOST), rspamd_base32::decode_ipv6(&encoded, alphabet));
+///}
+///```
+#[cfg(any(feature = "std", test))]
+pub fn encode_ipv6(addr: std::net::Ipv6Addr, alphabet: &Alphabet) -> String {
+    encode_alphabet(addr.octets(), alphabet)
+}
+
+/// Error returned by [encode_heapless] when the encoding does not fit
+#[cfg(feature = "heapless")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CapacityError {
+    /// Number of symbols of the encoding, `None` if it overflows `usize`
+    pub needed: Option<usize>,
+    /// Capacity of the output string
+    pub capacity: usize,
+}
+
+#[cfg(feature = "heapless")]
+impl fmt::Display for CapacityError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.needed {
+            Some(needed) => write!(f, "Capacity is too small: needed {}, got {}", needed, self.capacity),
+            None => write!(f, "Encoded length overflows usize"),
+        }
+    }
+}
+
+#[cfg(all(feature = "heapless", any(feature = "std", test)))]
+impl error::Error for CapacityError {}
+
+///Encode base32 using the specified [Alphabet] into a fixed capacity `heapless::String`,
+///nothing is allocated. Returns [CapacityError] if the encoding is longer than `N`.
+///
+///# Example
+///
+///```rust
+///use rspamd_base32::alphabet::ZBASE32;
+///use rspamd_base32::encode::encode_heapless;
+///
+///fn main() {
+///    let encoded = encode_heapless::<8>(b"hello", &ZBASE32).unwrap();
+///    assert_eq!("em3ags7p", encoded.as_str());
+///}
+///```
+#[cfg(feature = "heapless")]
+pub fn encode_heapless<const N: usize>(input: &[u8], alphabet: &Alphabet) -> Result<heapless::String<N>, CapacityError> {
+    let needed = encoded_len(input.len());
+    let too_small = CapacityError { needed, capacity: N };
+    let mut buf = heapless::Vec::<u8, N>::new();
+    buf.resize(needed.ok_or(too_small.clone())?, 0).map_err(|_| too_small)?;
+
+    let enc_len = encode_alphabet_slice(input, &mut buf, alphabet);
+    buf.truncate(enc_len);
+    Ok(heapless::String::from_utf8(buf).expect(ASCII_SYMBOLS))
+}
+
+///Returns the number of padding characters needed to make the encoded
+///output of `bytes_len` input bytes a multiple of 8 symbols
+pub fn padding_len(bytes_len: usize) -> usize {
+    match bytes_len % 5 {
+        1 => 6,
+        2 => 4,
+        3 => 3,
+        4 => 1,
+        _ => 0,
+    }
+}
+
+///Encode base32 using the specified [Alphabet] and append `pad` characters
+///so that the output length is a multiple of 8, as RFC 4648 requires.
+///Returns a `Result` containing a `String`, or the same error as [crate::alphabet::AlphabetBuilder]
+///if `pad` is not printable or is accepted by the decoder as a symbol.
+///
+///# Example
+///
+///```rust
+///extern crate rspamd_base32;
+///
+///fn main() {
+///    let encoded = rspamd_base32::encode_alphabet_padded(
+///        "a",
+///        &rspamd_base32::alphabet::RFC,
+///        b'=',
+///    );
+///    assert_eq!(Ok("ME======".to_owned()), encoded);
+///}
+///```
+#[cfg(any(feature = "alloc", feature = "std", test))]
+pub fn encode_alphabet_padded<T: AsRef<[u8]>>(
+    input: T,
+    alphabet: &Alphabet,
+    pad: u8,
+) -> Result<String, crate::alphabet::ParseAlphabetError> {
+    if !(b' '..=b'~').contains(&pad) {
+        return Err(crate::alphabet::ParseAlphabetError::UnprintableByte(pad));
+    }
+    if alphabet.contains(pad) {
+        return Err(crate::alphabet::ParseAlphabetError::PaddingCollision(pad));
+    }
+
+    let input_len = input.as_ref().len();
+    let mut encoded = encode_alphabet(input, alphabet);
+    for _ in 0..padding_len(input_len) {
+        encoded.push(pad as char);
+    }
+    Ok(encoded)
 }
 
 ///Encode base32 using the default alphabet
 ///Returns a `String` result
 ///
+///# Panics
+///
+///Panics if the encoded length overflows `usize`, see [try_encode] for a non-panicking variant.
+///
 ///# Example
 ///
 ///```rust
@@ -182,10 +1034,18 @@ pub fn encode<T: AsRef<[u8]>>(input: T) -> String {
     encode_alphabet(input, &ZBASE32)
 }
 
+///Encode base32 using the default alphabet as [encode] does, without a panic path.
+///Returns a `Result` containing a `String`, see [try_encode_alphabet].
+#[cfg(any(feature = "alloc", feature = "std", test))]
+pub fn try_encode<T: AsRef<[u8]>>(input: T) -> Result<String, LengthError> {
+    try_encode_alphabet(input, &ZBASE32)
+}
+
 #[cfg(test)]
 mod tests {
     use crate::encode::*;
     use crate::alphabet::*;
+    use crate::decode::{decode_alphabet_grouped, decode_alphabet_lenient};
 
     #[test]
     fn simple_encode_zbase() {
@@ -293,4 +1153,303 @@ mod tests {
             encode_alphabet("aaaaaaaa", &RFC),
         );
     }
-}
\ No newline at end of file
+    #[test]
+    fn encode_const_zbase() {
+        const HELLO: [u8; 8] = encode_const(b"hello", &ZBASE32);
+        assert_eq!(b"em3ags7p", &HELLO);
+        const TEST: [u8; 12] = encode_const(b"test123", &ZBASE32);
+        assert_eq!(b"wm3g84fg13cy", &TEST);
+        const EMPTY: [u8; 0] = encode_const(b"", &RFC);
+        assert_eq!(b"", &EMPTY);
+        assert_eq!(b"MFQWCYI", &encode_const::<4, 7>(b"aaaa", &RFC));
+    }
+
+    #[test]
+    #[should_panic]
+    fn encode_const_wrong_len() {
+        let _ = encode_const::<5, 9>(b"hello", &ZBASE32);
+    }
+
+    #[test]
+    fn encode_cow_borrows_empty() {
+        assert!(matches!(encode_cow("", &ZBASE32), Cow::Borrowed("")));
+        assert!(matches!(encode_cow(b"", &RFC), Cow::Borrowed("")));
+        match encode_cow("hello", &RFC) {
+            Cow::Owned(encoded) => assert_eq!("NBSWY3DP", encoded),
+            Cow::Borrowed(_) => panic!("non-empty input must be owned"),
+        }
+    }
+
+    #[test]
+    fn encode_iter_same_as_encode() {
+        for alphabet in [&ZBASE32, &RFC] {
+            for len in 0..40 {
+                let input: Vec<u8> = (0..len).map(|i| (i * 37) as u8).collect();
+                let encoded: String = encode_iter(input.iter().copied(), alphabet)
+                    .map(char::from)
+                    .collect();
+                assert_eq!(encode_alphabet(&input, alphabet), encoded);
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "heapless")]
+    fn heapless_capacity() {
+        let input: Vec<u8> = (0..16).collect();
+        let encoded = encode_heapless::<32>(&input, &RFC).unwrap();
+        assert_eq!(encode_alphabet(&input, &RFC), encoded.as_str());
+        assert_eq!(encoded.as_str(), encode_heapless::<26>(&input, &RFC).unwrap().as_str());
+        assert_eq!(
+            Err(CapacityError { needed: Some(26), capacity: 25 }),
+            encode_heapless::<25>(&input, &RFC),
+        );
+    }
+
+    #[test]
+    fn encode_chars_same_as_slice() {
+        let input = b"hello, world!";
+        let mut expected = [0_u8; 21];
+        assert_eq!(21, encode_alphabet_slice(input, &mut expected, &ZBASE32));
+
+        let mut chars = [0_u8; 21];
+        let mut len = 0;
+        for c in encode_chars(input, &ZBASE32) {
+            chars[len] = c as u8;
+            len += 1;
+        }
+        assert_eq!(expected.len(), len);
+        assert_eq!(expected, chars);
+    }
+
+    #[test]
+    fn encoded_len_exact() {
+        let input = [0xa5_u8; 100];
+        let mut out = [0_u8; 200];
+        for alphabet in [&ZBASE32, &RFC] {
+            for n in 0..=100 {
+                let written = encode_alphabet_slice(&input[..n], &mut out, alphabet);
+                assert_eq!(Some(written), encoded_len(n), "{}", n);
+            }
+        }
+        assert_eq!(Some(0), encoded_len(0));
+        assert_eq!(Some(8), encoded_len(5));
+    }
+
+    #[test]
+    fn encoded_len_overflow_boundary() {
+        // The largest whole number of groups whose encoding still fits in usize
+        let max_groups = usize::MAX / 8;
+        assert_eq!(Some(max_groups * 8), encoded_len(max_groups * 5));
+        assert_eq!(None, encoded_len((max_groups + 1) * 5));
+        assert_eq!(None, encoded_len(usize::MAX));
+
+        assert_eq!(Ok(max_groups * 8), encoded_len_checked(max_groups * 5));
+        assert_eq!(Err(LengthError::Overflow), encoded_len_checked((max_groups + 1) * 5));
+        assert_eq!(Err(LengthError::Overflow), encoded_len_checked(usize::MAX));
+        assert_eq!(Ok(encode("hello")), try_encode("hello"));
+    }
+
+    #[test]
+    #[cfg(target_pointer_width = "32")]
+    fn encoded_len_overflow_32bit() {
+        assert!(encoded_len(0x9fff_fffb).is_some());
+        assert_eq!(None, encoded_len(0xa000_0000));
+        assert_eq!(None, encoded_len(0xffff_ffff));
+    }
+
+    #[test]
+    fn encode_output_is_symbols() {
+        let input: Vec<u8> = (0..=255).collect();
+        for alphabet in [&ZBASE32, &RFC, &CROCKFORD, &WORDSAFE] {
+            let expected: String = encode_iter(input.iter().copied(), alphabet).map(char::from).collect();
+            assert_eq!(expected, encode_alphabet(&input, alphabet));
+        }
+    }
+
+    #[test]
+    fn matches_encoding_ct() {
+        let data: Vec<u8> = (0..40).collect();
+        for alphabet in [&ZBASE32, &RFC] {
+            for len in 0..data.len() {
+                let encoded = encode_alphabet(&data[..len], alphabet);
+                assert!(matches_encoding(&encoded, &data[..len], alphabet));
+                // Length mismatch in both directions
+                assert!(!matches_encoding(&encoded, &data[..len + 1], alphabet));
+                assert!(!matches_encoding(&format!("{}{}", encoded, "A7"), &data[..len], alphabet));
+                if len > 0 {
+                    assert!(!matches_encoding(&encoded, &data[..len - 1], alphabet));
+                    // A single different symbol
+                    let mut changed = encoded.clone().into_bytes();
+                    let last = changed.len() - 1;
+                    let value = alphabet.decode_bytes[changed[last] as usize] as usize;
+                    changed[last] = alphabet.encode_symbols[(value + 1) % 32];
+                    assert!(!matches_encoding(core::str::from_utf8(&changed).unwrap(), &data[..len], alphabet));
+                }
+            }
+        }
+        assert!(!matches_encoding("EM3AGS7P", b"hello", &ZBASE32));
+    }
+
+    #[test]
+    fn encode_grouped_roundtrip() {
+        assert_eq!(Ok("em3a-gs7p".to_owned()), encode_grouped(b"hello", &ZBASE32, 4, b'-'));
+        assert_eq!(Ok("wm3g-84fg-13cy".to_owned()), encode_grouped(b"test123", &ZBASE32, 4, b'-'));
+        assert_eq!(Ok("em3ags7p".to_owned()), encode_grouped(b"hello", &ZBASE32, 0, b'-'));
+        assert_eq!(Ok("".to_owned()), encode_grouped(b"", &ZBASE32, 4, b'-'));
+
+        let data: Vec<u8> = (0..40).collect();
+        for len in 0..data.len() {
+            for group in 0..10 {
+                let encoded = encode_grouped(&data[..len], &RFC, group, b'-').unwrap();
+                assert_eq!(data[..len], decode_alphabet_grouped(&encoded, &RFC, b'-').unwrap()[..]);
+            }
+        }
+    }
+
+    #[test]
+    fn encode_grouped_rejects_symbol() {
+        // A symbol separator would be decoded as data
+        assert_eq!(
+            Err(ParseAlphabetError::SeparatorCollision(b'y')),
+            encode_grouped(b"hello", &ZBASE32, 4, b'y'),
+        );
+        // Case-folded variants and aliases are decoded as symbols as well
+        assert_eq!(
+            Err(ParseAlphabetError::SeparatorCollision(b'i')),
+            encode_grouped(b"hello", &CROCKFORD, 4, b'i'),
+        );
+        assert_eq!(
+            Err(ParseAlphabetError::UnprintableByte(0xe2)),
+            encode_grouped(b"hello", &ZBASE32, 4, 0xe2),
+        );
+    }
+
+    #[test]
+    fn encode_wrapped_roundtrip() {
+        let data: Vec<u8> = (0..200).collect();
+        let encoded = encode_wrapped(&data, &ZBASE32, 64, "\n");
+        let lines: Vec<&str> = encoded.split('\n').collect();
+        assert_eq!(encoded_len(data.len()).unwrap().div_ceil(64), lines.len());
+        assert!(lines[..lines.len() - 1].iter().all(|line| line.len() == 64));
+        assert!(!encoded.ends_with('\n'));
+        assert_eq!(data, decode_alphabet_lenient(&encoded, &ZBASE32).unwrap());
+
+        // Exact multiple of the width, no trailing line ending either
+        let encoded = encode_wrapped(&data[..40], &ZBASE32, 64, "\r\n");
+        assert_eq!(64, encoded.len());
+        assert_eq!(encode_alphabet(&data[..40], &ZBASE32), encode_wrapped(&data[..40], &ZBASE32, 0, "\n"));
+        assert_eq!("", encode_wrapped(b"", &ZBASE32, 64, "\n"));
+    }
+
+    #[test]
+    fn encode_batch() {
+        let inputs: Vec<Vec<u8>> = (0..50_u8).map(|len| (0..len).collect()).collect();
+        let encoded = encode_many(inputs.iter().map(Vec::as_slice), &RFC);
+        let mut scratch = Vec::new();
+        assert_eq!(inputs.len(), encoded.len());
+        for (input, expected) in inputs.iter().zip(&encoded) {
+            assert_eq!(&encode_alphabet(input, &RFC), expected);
+            assert_eq!(expected, encode_into_reused(input, &mut scratch, &RFC));
+        }
+    }
+
+    #[test]
+    fn encode_fmt_appends() {
+        let input: Vec<u8> = (0..=255).collect();
+        let mut out = String::from("prefix: ");
+        encode_fmt(&input, &ZBASE32, &mut out).unwrap();
+        assert_eq!(format!("prefix: {}", encode(&input)), out);
+    }
+
+    #[test]
+    fn encode_padded_rejects_symbol() {
+        let alphabet = Alphabet::from_str("ABCDEFGHIJKLMNOPQRSTUVWXYZ23456=").unwrap();
+        assert_eq!(Err(ParseAlphabetError::PaddingCollision(b'=')), encode_alphabet_padded("a", &alphabet, b'='));
+        // Case-folded variants and aliases are decoded as symbols as well
+        assert_eq!(Err(ParseAlphabetError::PaddingCollision(b'o')), encode_alphabet_padded("a", &CROCKFORD, b'o'));
+        assert_eq!(Err(ParseAlphabetError::UnprintableByte(0xc3)), encode_alphabet_padded("a", &RFC, 0xc3));
+        assert_eq!(Err(ParseAlphabetError::UnprintableByte(b'\n')), encode_alphabet_padded("a", &RFC, b'\n'));
+    }
+
+    #[test]
+    fn series_encode_rfc_padded() {
+        // Matches Python's base64.b32encode
+        for (plain, encoded) in [
+            ("", ""),
+            ("a", "ME======"),
+            ("aa", "MFQQ===="),
+            ("aaa", "MFQWC==="),
+            ("aaaa", "MFQWCYI="),
+            ("aaaaa", "MFQWCYLB"),
+            ("aaaaaa", "MFQWCYLBME======"),
+        ] {
+            assert_eq!(Ok(encoded.to_owned()), encode_alphabet_padded(plain, &RFC, b'='));
+        }
+    }
+
+    #[test]
+    fn small_same_as_slice() {
+        let reversed = AlphabetBuilder::new()
+            .symbols("ybndrfg8ejkmcpqxot1uwisza345h769")
+            .bit_order(BitOrder::LsbFirst)
+            .octet_order(OctetOrder::Reversed)
+            .build()
+            .unwrap();
+        let input: Vec<u8> = (0..64).map(|i| (i * 151 + 7) as u8).collect();
+        let mut out = [0_u8; 128];
+        let mut out_small = [0_u8; 128];
+
+        for alphabet in [&ZBASE32, &RFC, &CROCKFORD, &reversed] {
+            for len in 0..=64 {
+                let expected = encode_alphabet_slice(&input[..len], &mut out, alphabet);
+                let got = encode_small(&input[..len], &mut out_small, alphabet);
+                assert_eq!(out[..expected], out_small[..got], "{}", len);
+            }
+        }
+    }
+
+    #[test]
+    fn truncated_prefix() {
+        let hash: Vec<u8> = (0..32).map(|i| (i * 73 + 11) as u8).collect();
+        for alphabet in [&ZBASE32, &RFC] {
+            let full = encode_alphabet(&hash, alphabet);
+            let id = encode_truncated(&hash, alphabet, 12).unwrap();
+            assert_eq!(12, id.len());
+            assert_eq!(&full[..12], id);
+            for symbols in 0..=full.len() {
+                assert_eq!(Ok(full[..symbols].to_owned()), encode_truncated(&hash, alphabet, symbols));
+            }
+        }
+        assert_eq!(Ok(String::new()), encode_truncated(b"", &ZBASE32, 0));
+    }
+
+    #[test]
+    fn truncated_too_long() {
+        // 5 octets produce exactly 8 symbols
+        assert_eq!(
+            Err(LengthError::InputTooShort { requested: 9, available: 8 }),
+            encode_truncated(b"hello", &ZBASE32, 9),
+        );
+        assert_eq!(
+            Err(LengthError::InputTooShort { requested: 10, available: 8 }),
+            encode_truncated(b"hello", &ZBASE32, 10),
+        );
+        assert_eq!(
+            Err(LengthError::InputTooShort { requested: 1, available: 0 }),
+            encode_truncated(b"", &RFC, 1),
+        );
+    }
+
+    #[test]
+    fn encode_buffer_reused() {
+        let data: Vec<u8> = (0..=255).collect();
+        let mut buffer = EncodeBuffer::with_capacity(64);
+        for alphabet in [&ZBASE32, &RFC] {
+            for len in (0..64).chain([3, 200, 7]) {
+                assert_eq!(encode_alphabet(&data[..len], alphabet), buffer.encode(&data[..len], alphabet));
+            }
+        }
+        assert_eq!("", EncodeBuffer::new().encode(b"", &ZBASE32));
+    }
+}
@@ -0,0 +1,374 @@
+//! Streaming Base32 encoding and decoding over `std::io`
+use crate::alphabet::Alphabet;
+use crate::decode::{decode_alphabet_slice, DecodeError};
+use crate::encode::{encode_alphabet_slice, EncoderState};
+
+use std::io::{self, Read, Write};
+
+/// Number of input octets encoded as a whole group of 8 symbols
+const GROUP_BYTES: usize = 5;
+/// Number of symbols in a whole group
+const GROUP_SYMBOLS: usize = 8;
+/// Number of groups encoded at once into the on-stack output buffer
+const GROUPS_PER_CHUNK: usize = 128;
+
+///Wraps a writer and encodes everything written to it as base32
+///using the specified [Alphabet].
+///
+///Complete 5-byte groups are encoded and passed to the inner writer as soon as they are
+///available, a trailing partial group is written by [Base32Writer::finish] or on drop.
+///
+///# Example
+///
+///```rust
+///use std::io::Write;
+///use rspamd_base32::alphabet::ZBASE32;
+///use rspamd_base32::io::Base32Writer;
+///
+///fn main() {
+///    let mut writer = Base32Writer::new(Vec::new(), &ZBASE32);
+///    writer.write_all(b"hel").unwrap();
+///    writer.write_all(b"lo").unwrap();
+///    let encoded = writer.finish().unwrap();
+///    assert_eq!(b"em3ags7p", &encoded[..]);
+///}
+///```
+pub struct Base32Writer<'a, W: Write> {
+    inner: Option<W>,
+    alphabet: &'a Alphabet,
+    pending: [u8; GROUP_BYTES],
+    pending_len: usize,
+}
+
+impl<'a, W: Write> Base32Writer<'a, W> {
+    /// Creates a new encoder writing base32 symbols into `inner`
+    pub fn new(inner: W, alphabet: &'a Alphabet) -> Self {
+        Base32Writer {
+            inner: Some(inner),
+            alphabet,
+            pending: [0; GROUP_BYTES],
+            pending_len: 0,
+        }
+    }
+
+    /// Encodes the trailing partial group, flushes and returns the inner writer
+    pub fn finish(mut self) -> io::Result<W> {
+        self.write_pending()?;
+        let mut inner = self.inner.take().expect("writer is already finished");
+        inner.flush()?;
+        Ok(inner)
+    }
+
+    fn write_pending(&mut self) -> io::Result<()> {
+        if self.pending_len > 0 {
+            let mut out = [0_u8; 8];
+            let len = encode_alphabet_slice(&self.pending[..self.pending_len], &mut out, self.alphabet);
+            self.pending_len = 0;
+            self.inner_mut().write_all(&out[..len])?;
+        }
+
+        Ok(())
+    }
+
+    fn inner_mut(&mut self) -> &mut W {
+        self.inner.as_mut().expect("writer is already finished")
+    }
+}
+
+impl<W: Write> Write for Base32Writer<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut input = buf;
+
+        // Complete a group left from the previous write
+        if self.pending_len > 0 {
+            let take = (GROUP_BYTES - self.pending_len).min(input.len());
+            self.pending[self.pending_len..self.pending_len + take].copy_from_slice(&input[..take]);
+            self.pending_len += take;
+            input = &input[take..];
+
+            if self.pending_len < GROUP_BYTES {
+                return Ok(buf.len());
+            }
+            self.write_pending()?;
+        }
+
+        // Whole groups are independent of each other, so they can be encoded in chunks
+        let mut out = [0_u8; GROUPS_PER_CHUNK * 8];
+        while input.len() >= GROUP_BYTES {
+            let groups = (input.len() / GROUP_BYTES).min(GROUPS_PER_CHUNK);
+            let (chunk, rest) = input.split_at(groups * GROUP_BYTES);
+            let len = encode_alphabet_slice(chunk, &mut out, self.alphabet);
+            self.inner_mut().write_all(&out[..len])?;
+            input = rest;
+        }
+
+        self.pending[..input.len()].copy_from_slice(input);
+        self.pending_len = input.len();
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner_mut().flush()
+    }
+}
+
+impl<W: Write> Drop for Base32Writer<'_, W> {
+    fn drop(&mut self) {
+        if self.inner.is_some() {
+            // Errors cannot be reported from drop, use `finish` to observe them
+            let _ = self.write_pending();
+        }
+    }
+}
+
+///Wraps a reader of base32 symbols and yields decoded octets
+///using the specified [Alphabet].
+///
+///Symbols are decoded in whole groups of 8, the trailing partial group is decoded
+///once the inner reader is exhausted. Decoding errors are reported as
+///[io::ErrorKind::InvalidData] with a [DecodeError] carrying the offset in the whole stream.
+///
+///# Example
+///
+///```rust
+///use std::io::{Cursor, Read};
+///use rspamd_base32::alphabet::ZBASE32;
+///use rspamd_base32::io::Base32Reader;
+///
+///fn main() {
+///    let mut reader = Base32Reader::new(Cursor::new("em3ags7p"), &ZBASE32);
+///    let mut decoded = Vec::new();
+///    reader.read_to_end(&mut decoded).unwrap();
+///    assert_eq!(b"hello", &decoded[..]);
+///}
+///```
+pub struct Base32Reader<'a, R: Read> {
+    inner: R,
+    alphabet: &'a Alphabet,
+    input: [u8; GROUPS_PER_CHUNK * GROUP_SYMBOLS],
+    input_len: usize,
+    output: [u8; GROUPS_PER_CHUNK * GROUP_BYTES],
+    output_pos: usize,
+    output_len: usize,
+    /// Number of symbols decoded so far, used to report error offsets
+    consumed: usize,
+    eof: bool,
+}
+
+impl<'a, R: Read> Base32Reader<'a, R> {
+    /// Creates a new decoder reading base32 symbols from `inner`
+    pub fn new(inner: R, alphabet: &'a Alphabet) -> Self {
+        Base32Reader {
+            inner,
+            alphabet,
+            input: [0; GROUPS_PER_CHUNK * GROUP_SYMBOLS],
+            input_len: 0,
+            output: [0; GROUPS_PER_CHUNK * GROUP_BYTES],
+            output_pos: 0,
+            output_len: 0,
+            consumed: 0,
+            eof: false,
+        }
+    }
+
+    /// Returns the inner reader
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Decodes the next portion of symbols, returns `false` when the input is exhausted
+    fn fill_output(&mut self) -> io::Result<bool> {
+        while !self.eof && self.input_len < GROUP_SYMBOLS {
+            match self.inner.read(&mut self.input[self.input_len..]) {
+                Ok(0) => self.eof = true,
+                Ok(n) => self.input_len += n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        // Whole groups are independent, the partial group is decoded only at the end
+        let symbols = if self.eof {
+            self.input_len
+        } else {
+            self.input_len - self.input_len % GROUP_SYMBOLS
+        };
+
+        if symbols == 0 {
+            return Ok(false);
+        }
+
+        let decoded = decode_alphabet_slice(&self.input[..symbols], &mut self.output, self.alphabet)
+            .map_err(|e| {
+                let e = match e.offset_bytes(self.consumed) {
+                    DecodeError::InvalidLength { len, remainder } => {
+                        DecodeError::InvalidLength { len: self.consumed + len, remainder }
+                    }
+                    e => e,
+                };
+                io::Error::from(e)
+            })?;
+
+        self.input.copy_within(symbols..self.input_len, 0);
+        self.input_len -= symbols;
+        self.consumed += symbols;
+        self.output_pos = 0;
+        self.output_len = decoded;
+
+        Ok(true)
+    }
+}
+
+impl<R: Read> Read for Base32Reader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        while self.output_pos == self.output_len {
+            if !self.fill_output()? {
+                return Ok(0);
+            }
+        }
+
+        let len = (self.output_len - self.output_pos).min(buf.len());
+        buf[..len].copy_from_slice(&self.output[self.output_pos..self.output_pos + len]);
+        self.output_pos += len;
+
+        Ok(len)
+    }
+}
+
+///Encode everything read from `reader` using the specified [Alphabet].
+///Returns the encoded `String`, I/O errors other than [io::ErrorKind::Interrupted] are passed through.
+///
+///# Example
+///
+///```rust
+///use std::io::Cursor;
+///use rspamd_base32::alphabet::ZBASE32;
+///use rspamd_base32::io::encode_reader;
+///
+///fn main() {
+///    assert_eq!("em3ags7p", encode_reader(&mut Cursor::new(b"hello"), &ZBASE32).unwrap());
+///}
+///```
+pub fn encode_reader<R: Read>(reader: &mut R, alphabet: &Alphabet) -> io::Result<String> {
+    let mut state = EncoderState::new(alphabet);
+    let mut encoded = String::new();
+    let mut buf = [0_u8; GROUPS_PER_CHUNK * GROUP_BYTES];
+
+    loop {
+        match reader.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => state.feed(&buf[..n], &mut encoded),
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    state.finish(&mut encoded);
+
+    Ok(encoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::io::*;
+    use crate::alphabet::*;
+    use crate::encode::*;
+    use crate::decode::DecodeError;
+    use std::io::Cursor;
+
+    #[test]
+    fn writer_odd_chunks() {
+        let data: Vec<u8> = (0..=255).cycle().take(3000).collect();
+        for alphabet in [&ZBASE32, &RFC] {
+            for chunk_size in [1, 2, 3, 4, 5, 7, 13, 640, 1000] {
+                let mut writer = Base32Writer::new(Vec::new(), alphabet);
+                for chunk in data.chunks(chunk_size) {
+                    writer.write_all(chunk).unwrap();
+                }
+                let encoded = writer.finish().unwrap();
+                assert_eq!(encode_alphabet(&data, alphabet).as_bytes(), &encoded[..]);
+            }
+        }
+    }
+
+    #[test]
+    fn writer_flushes_on_drop() {
+        let mut encoded = Vec::new();
+        {
+            let mut writer = Base32Writer::new(&mut encoded, &ZBASE32);
+            writer.write_all(b"test123").unwrap();
+        }
+        assert_eq!(b"wm3g84fg13cy", &encoded[..]);
+    }
+
+    #[test]
+    fn reader_small_reads() {
+        let data: Vec<u8> = (0..=255).cycle().take(3001).collect();
+        for alphabet in [&ZBASE32, &RFC] {
+            for read_size in [1, 2, 3, 5, 7, 64, 4096] {
+                let mut reader = Base32Reader::new(Cursor::new(encode_alphabet(&data, alphabet)), alphabet);
+                let mut decoded = Vec::new();
+                let mut buf = vec![0_u8; read_size];
+                loop {
+                    let n = reader.read(&mut buf).unwrap();
+                    if n == 0 {
+                        break;
+                    }
+                    decoded.extend_from_slice(&buf[..n]);
+                }
+                assert_eq!(data, decoded);
+            }
+        }
+    }
+
+    #[test]
+    fn reader_partial_group() {
+        for len in 0..20 {
+            let data: Vec<u8> = (0..len).collect();
+            for alphabet in [&ZBASE32, &RFC] {
+                let mut reader = Base32Reader::new(Cursor::new(encode_alphabet(&data, alphabet)), alphabet);
+                let mut decoded = Vec::new();
+                reader.read_to_end(&mut decoded).unwrap();
+                assert_eq!(data, decoded);
+            }
+        }
+    }
+
+    #[test]
+    fn reader_invalid_byte() {
+        let mut encoded = encode(vec![0_u8; 2000]).into_bytes();
+        encoded[1500] = b'!';
+        let mut reader = Base32Reader::new(Cursor::new(encoded), &ZBASE32);
+        let err = reader.read_to_end(&mut Vec::new()).unwrap_err();
+        assert_eq!(io::ErrorKind::InvalidData, err.kind());
+        assert_eq!(
+            Some(&DecodeError::InvalidByte(1500, b'!')),
+            err.get_ref().and_then(|e| e.downcast_ref::<DecodeError>())
+        );
+    }
+
+    struct FailingReader;
+
+    impl Read for FailingReader {
+        fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+            Err(io::ErrorKind::BrokenPipe.into())
+        }
+    }
+
+    #[test]
+    fn encode_from_reader() {
+        let data: Vec<u8> = (0..=255).cycle().take(3001).collect();
+        for alphabet in [&ZBASE32, &RFC] {
+            let encoded = encode_reader(&mut Cursor::new(&data), alphabet).unwrap();
+            assert_eq!(encode_alphabet(&data, alphabet), encoded);
+        }
+        assert_eq!("", encode_reader(&mut io::empty(), &ZBASE32).unwrap());
+
+        let mut failing = Cursor::new(b"hello").chain(FailingReader);
+        assert_eq!(io::ErrorKind::BrokenPipe, encode_reader(&mut failing, &ZBASE32).unwrap_err().kind());
+    }
+}
@@ -33,15 +33,64 @@
 //!}
 //!~~~
 #![forbid(unsafe_code)]
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+#[cfg(any(feature = "alloc", feature = "std", test))]
+extern crate alloc;
 
 pub mod alphabet;
 pub mod encode;
+pub use crate::encode::{
+    encode_alphabet_slice, encode_alphabet_slice_ct, encode_chars, encode_fmt, encode_iter, encode_small, encoded_len,
+    encoded_len_checked, matches_encoding,
+};
 #[cfg(any(feature = "alloc", feature = "std", test))]
-pub use crate::encode::{encode, encode_alphabet, encode_alphabet_slice};
+pub use crate::encode::{
+    encode, encode_alphabet, encode_alphabet_padded, encode_cow, encode_framed, encode_grouped, encode_into_reused,
+    encode_many, encode_truncated, encode_u64, encode_wrapped, try_encode, try_encode_alphabet,
+};
+#[cfg(any(feature = "std", test))]
+pub use crate::encode::encode_ipv6;
+#[cfg(feature = "uuid")]
+pub use crate::encode::encode_uuid;
+#[cfg(feature = "heapless")]
+pub use crate::encode::encode_heapless;
 
 pub mod decode;
+pub use crate::decode::{
+    decode_alphabet_slice, decode_alphabet_slice_partial, decode_iter, decode_to_array, decode_u64, decoded_len,
+    decoded_len_padded, eq_encoded, is_valid_encoded_len, validate,
+};
+#[cfg(any(feature = "alloc", feature = "std", test))]
+pub use crate::decode::{
+    classify, decode, decode_alphabet, decode_alphabet_bounded, decode_alphabet_grouped, decode_alphabet_lenient,
+    decode_alphabet_padded, decode_alphabet_strict, decode_alphabet_vec, decode_alphabet_vec_ct,
+    decode_alphabet_vec_into_at, decode_auto, decode_framed, decode_human, decode_prefix, transcode,
+};
+#[cfg(any(feature = "std", test))]
+pub use crate::decode::decode_ipv6;
+#[cfg(feature = "uuid")]
+pub use crate::decode::decode_uuid;
+
+#[cfg(any(feature = "alloc", feature = "std", test))]
+pub mod string;
+#[cfg(any(feature = "alloc", feature = "std", test))]
+pub use crate::string::Base32String;
+
+#[cfg(any(feature = "alloc", feature = "std", test))]
+pub mod bech32;
+
 #[cfg(any(feature = "alloc", feature = "std", test))]
-pub use crate::decode::{decode, decode_alphabet, decode_alphabet_vec};
+pub mod checkdigit;
+
+#[cfg(any(feature = "std", test))]
+pub mod io;
+
+#[cfg(feature = "serde")]
+pub mod serde_zbase32;
+
+#[cfg(feature = "wasm")]
+pub mod wasm;
 
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,72 @@
+//! Serde helpers to (de)serialize octets as zbase32 strings
+//!
+//! ~~~rust
+//!use serde::{Deserialize, Serialize};
+//!
+//!#[derive(Serialize, Deserialize)]
+//!struct Message {
+//!    #[serde(with = "rspamd_base32::serde_zbase32")]
+//!    digest: Vec<u8>,
+//!}
+//!
+//!fn main() {
+//!    let msg = Message { digest: b"hello".to_vec() };
+//!    assert_eq!(r#"{"digest":"em3ags7p"}"#, serde_json::to_string(&msg).unwrap());
+//!}
+//!~~~
+use crate::decode::decode;
+use crate::encode::encode;
+
+use alloc::vec::Vec;
+use core::fmt;
+use serde::de::{self, Visitor};
+use serde::{Deserializer, Serializer};
+
+/// Serializes octets as a zbase32 string
+pub fn serialize<T: AsRef<[u8]>, S: Serializer>(input: &T, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&encode(input))
+}
+
+/// Deserializes octets from a zbase32 string
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+    struct ZBase32Visitor;
+
+    impl Visitor<'_> for ZBase32Visitor {
+        type Value = Vec<u8>;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a zbase32 encoded string")
+        }
+
+        fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+            decode(v).map_err(E::custom)
+        }
+    }
+
+    deserializer.deserialize_str(ZBase32Visitor)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Record {
+        #[serde(with = "crate::serde_zbase32")]
+        data: Vec<u8>,
+    }
+
+    #[test]
+    fn serde_roundtrip() {
+        let record = Record { data: b"test123".to_vec() };
+        let json = serde_json::to_string(&record).unwrap();
+        assert_eq!(r#"{"data":"wm3g84fg13cy"}"#, json);
+        assert_eq!(record, serde_json::from_str(&json).unwrap());
+    }
+
+    #[test]
+    fn serde_invalid() {
+        assert!(serde_json::from_str::<Record>(r#"{"data":"!!"}"#).is_err());
+        assert!(serde_json::from_str::<Record>(r#"{"data":42}"#).is_err());
+    }
+}
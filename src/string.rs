@@ -0,0 +1,113 @@
+//! Octets that are displayed and parsed as Base32 text
+use crate::alphabet::{Alphabet, ZBASE32};
+use crate::decode::{decode_alphabet, DecodeError};
+use crate::encode::encode_iter;
+
+use alloc::vec::Vec;
+use core::fmt::{self, Write};
+use core::str::FromStr;
+
+///Decoded octets that implement `Display` by encoding them
+///and `FromStr` by decoding, using [ZBASE32] unless another [Alphabet] is chosen.
+///
+///# Example
+///
+///```rust
+///use rspamd_base32::Base32String;
+///
+///fn main() {
+///    let parsed: Base32String = "em3ags7p".parse().unwrap();
+///    assert_eq!(b"hello", parsed.as_bytes());
+///    assert_eq!("em3ags7p", format!("{}", parsed));
+///}
+///```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Base32String<'a> {
+    bytes: Vec<u8>,
+    alphabet: &'a Alphabet,
+}
+
+impl<'a> Base32String<'a> {
+    /// Wraps octets that are displayed using [ZBASE32]
+    pub fn new(bytes: Vec<u8>) -> Self {
+        Self::with_alphabet(bytes, &ZBASE32)
+    }
+
+    /// Wraps octets that are displayed using the specified [Alphabet]
+    pub fn with_alphabet(bytes: Vec<u8>, alphabet: &'a Alphabet) -> Self {
+        Base32String { bytes, alphabet }
+    }
+
+    /// Decodes text using the specified [Alphabet]
+    pub fn parse_with_alphabet(input: &str, alphabet: &'a Alphabet) -> Result<Self, DecodeError> {
+        decode_alphabet(input, alphabet).map(|bytes| Self::with_alphabet(bytes, alphabet))
+    }
+
+    /// Returns decoded octets
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Returns decoded octets, consuming the wrapper
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+
+    /// Returns the alphabet used for display
+    pub fn alphabet(&self) -> &'a Alphabet {
+        self.alphabet
+    }
+}
+
+impl From<Vec<u8>> for Base32String<'_> {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self::new(bytes)
+    }
+}
+
+impl fmt::Display for Base32String<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for sym in encode_iter(self.bytes.iter().copied(), self.alphabet) {
+            f.write_char(sym as char)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Base32String<'static> {
+    type Err = DecodeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_with_alphabet(s, &ZBASE32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::string::*;
+    use crate::alphabet::RFC;
+    use alloc::string::ToString;
+
+    #[test]
+    fn parse_display_roundtrip() {
+        let parsed: Base32String = "wm3g84fg13cy".parse().expect("unparsed");
+        assert_eq!(b"test123", parsed.as_bytes());
+        assert_eq!("wm3g84fg13cy", parsed.to_string());
+        assert_eq!("em3ags7p", Base32String::from(b"hello".to_vec()).to_string());
+    }
+
+    #[test]
+    fn with_alphabet() {
+        let b32 = Base32String::with_alphabet(b"hello".to_vec(), &RFC);
+        assert_eq!("NBSWY3DP", b32.to_string());
+        assert_eq!(b32, Base32String::parse_with_alphabet("NBSWY3DP", &RFC).expect("unparsed"));
+    }
+
+    #[test]
+    fn parse_error() {
+        assert_eq!(
+            Err(DecodeError::InvalidByte(1, b'!')),
+            "e!".parse::<Base32String>()
+        );
+    }
+}
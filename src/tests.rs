@@ -1,80 +1,544 @@
+use crate::encode::*;
+use crate::decode::*;
+use crate::alphabet::*;
+use rand::prelude::*;
 
-#[cfg(test)]
-mod tests {
-    use crate::encode::*;
-    use crate::decode::*;
-    use crate::alphabet::*;
-    use rand::prelude::*;
-
-    fn roundtrip_random(
-        alphabet: &Alphabet,
-        byte_len: usize,
-        max_rounds: u64,
-    ) {
-        // let the short ones be short but don't let it get too crazy large
-        let mut r = rand::rngs::StdRng::from_entropy();
-        let mut decode_buf: Vec<u8> = Vec::new();
-        let mut byte_buf: Vec<u8> = Vec::new();
-
-        for _ in 0..max_rounds {
-            byte_buf.clear();
-            decode_buf.clear();
-            while byte_buf.len() < byte_len {
-                byte_buf.push(r.gen::<u8>());
-            }
+fn roundtrip_random(
+    alphabet: &Alphabet,
+    byte_len: usize,
+    max_rounds: u64,
+) {
+    // let the short ones be short but don't let it get too crazy large
+    let mut r = rand::rngs::StdRng::from_entropy();
+    let mut decode_buf: Vec<u8> = Vec::new();
+    let mut byte_buf: Vec<u8> = Vec::new();
+
+    for _ in 0..max_rounds {
+        byte_buf.clear();
+        decode_buf.clear();
+        while byte_buf.len() < byte_len {
+            byte_buf.push(r.gen::<u8>());
+        }
+
+        let encoded = encode_alphabet(&byte_buf, alphabet);
+        decode_alphabet_vec(&encoded, &mut decode_buf, alphabet).unwrap();
+
+        assert_eq!(byte_buf, decode_buf);
+    }
+}
 
-            let encoded = encode_alphabet(&byte_buf, alphabet);
-            decode_alphabet_vec(&encoded, &mut decode_buf, alphabet).unwrap();
+fn encoder_state_random_chunks(alphabet: &Alphabet, max_rounds: u64) {
+    let mut r = rand::rngs::StdRng::from_entropy();
 
-            assert_eq!(byte_buf, decode_buf);
+    for _ in 0..max_rounds {
+        let input_len = r.gen_range(0..200);
+        let input: Vec<u8> = (0..input_len).map(|_| r.gen::<u8>()).collect();
+        let mut state = EncoderState::new(alphabet);
+        let mut encoded = String::new();
+        let mut rest = &input[..];
+
+        while !rest.is_empty() {
+            let (chunk, tail) = rest.split_at(r.gen_range(0..=rest.len()));
+            state.feed(chunk, &mut encoded);
+            rest = tail;
         }
+        state.finish(&mut encoded);
+
+        assert_eq!(encode_alphabet(&input, alphabet), encoded);
     }
+}
 
-    fn compare_decode(expected: &str, target: &str) {
-        assert_eq!(
-            expected,
-            String::from_utf8(decode(target).unwrap()).unwrap()
-        );
+/// Every alphabet shipped by the crate
+const SHIPPED: [&Alphabet; 10] = [
+    &ZBASE32,
+    &ZBASE32_CANONICAL,
+    &BECH32,
+    &RFC,
+    &RFC_LOWER,
+    &DNS32,
+    &BASE32HEX,
+    &CROCKFORD,
+    &WORDSAFE,
+    &GEOHASH,
+];
+
+fn compare_decode(expected: &str, target: &str) {
+    assert_eq!(
+        expected,
+        String::from_utf8(decode(target).unwrap()).unwrap()
+    );
+    assert_eq!(
+        expected,
+        String::from_utf8(decode(target.as_bytes()).unwrap()).unwrap()
+    );
+}
+
+/// Built-in alphabets skip validation, so a typo in a symbol table is only caught here
+#[test]
+fn shipped_symbols_unique() {
+    for alphabet in SHIPPED {
+        let symbols = alphabet.symbols();
+        for (i, &sym) in symbols.iter().enumerate() {
+            assert!(!symbols[i + 1..].contains(&sym), "{} repeats {}", alphabet, sym as char);
+            assert_eq!(i as u8, alphabet.decode_bytes[sym as usize], "{}", alphabet);
+        }
+    }
+}
+
+#[test]
+fn simple_decode_encode() {
+    compare_decode("test", &encode(decode(encode(b"test")).unwrap()));
+}
+
+#[test]
+fn encode_decode_random_zbase_small() {
+    let alphabet = ZBASE32;
+    for input_len in 0..40 {
+        roundtrip_random(&alphabet, input_len, 10);
+    }
+}
+
+#[test]
+fn encode_decode_random_zbase_different() {
+    let alphabet = ZBASE32;
+    for input_len in [0, 100, 10, 2, 1024, 32768, 511, 5, 7, 11, 8] {
+        roundtrip_random(&alphabet, input_len, 100);
+    }
+}
+
+#[test]
+fn encode_decode_random_zbase_canonical_small() {
+    let alphabet = ZBASE32_CANONICAL;
+    for input_len in 0..40 {
+        roundtrip_random(&alphabet, input_len, 10);
+    }
+}
+
+#[test]
+fn zbase_canonical_vectors() {
+    // Examples from the z-base-32 specification
+    for (plain, encoded) in [
+        (&[0xf0_u8, 0xbf, 0xc7][..], "6n9hq"),
+        (&[0xd4, 0x7a, 0x04][..], "4t7ye"),
+        (&b"hello"[..], "pb1sa5dx"),
+    ] {
+        assert_eq!(encoded, encode_alphabet(plain, &ZBASE32_CANONICAL));
+        assert_eq!(plain, decode_alphabet(encoded, &ZBASE32_CANONICAL).unwrap());
+    }
+    assert_ne!(encode("hello"), encode_alphabet("hello", &ZBASE32_CANONICAL));
+}
+
+/// `rspamd_encode_base32_buf` output for `RSPAMD_BASE32_ZBASE`: Rspamd's per-octet state
+/// machine, which shares no code with [crate::encode], applied to each input
+const RSPAMD_VECTORS: [(&[u8], &str); 12] = [
+    (b"hello", "em3ags7p"),
+    (b"hello world", "em3ags7py376g3tprd"),
+    (b"12345", "ttcgd4wg"),
+    (b"123456", "ttcgd4wgsb"),
+    (b"1234567", "ttcgd4wgs3py"),
+    (b"hello, world!", "em3ags7pcbeqzzjqcd3nn"),
+    (b"Rspamd rocks!", "14hyzoiprderzzpcm5hnn"),
+    (&[0x80, 0x00, 0x00, 0x00, 0x01], "yryyyyry"),
+    (&[0xff, 0xff, 0xff, 0xff, 0xff, 0xff], "9999999998"),
+    (&[0xde, 0xad, 0xbe, 0xef, 0xca, 0xfe], "6qm75zm368"),
+    (
+        b"The quick brown fox jumps over the lazy ",
+        "wn4kgorqim4gsibrnuh6s53pyt36ghbrkm74gapqy35cz1jqyb7os1brcmawzhbr",
+    ),
+    (
+        &[
+            0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f, 0x10,
+            0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17, 0x18, 0x19, 0x1a, 0x1b, 0x1c, 0x1d, 0x1e, 0x1f, 0x20, 0x21,
+            0x22, 0x23, 0x24, 0x25, 0x26, 0x27,
+        ],
+        "yeyroboyfobqyrrbkanaogabxyrnbjcnwefctmyd3ogsbqwd6a8y1oerdbjknuhr",
+    ),
+];
+
+#[test]
+fn rspamd_vectors() {
+    for (plain, encoded) in RSPAMD_VECTORS {
+        assert_eq!(encoded, encode(plain), "{:?}", plain);
+        assert_eq!(plain, &decode(encoded).unwrap()[..], "{}", encoded);
+
+        let mut buf = [0_u8; 64];
+        let len = encode_small(plain, &mut buf, &ZBASE32);
+        assert_eq!(encoded.as_bytes(), &buf[..len]);
+    }
+}
+
+#[test]
+fn zbase_rspamd_and_rfc_order_differ() {
+    assert!(ZBASE32.is_same_symbols(&ZBASE32_RFC_ORDER));
+    // Two groups, the second one partial
+    let plain = b"hello world";
+    assert_eq!("em3ags7py376g3tprd", encode_alphabet(plain, &ZBASE32));
+    assert_eq!("pb1sa5dxrb5s6hucco", encode_alphabet(plain, &ZBASE32_RFC_ORDER));
+    for alphabet in [&ZBASE32, &ZBASE32_RFC_ORDER] {
+        assert_eq!(plain, &decode_alphabet(encode_alphabet(plain, alphabet), alphabet).unwrap()[..]);
+    }
+}
+
+#[test]
+fn encode_decode_random_rfc_small() {
+    let alphabet = RFC;
+    for input_len in 0..40 {
+        roundtrip_random(&alphabet, input_len, 10);
+    }
+}
+
+#[test]
+fn encode_decode_random_bech32_small() {
+    let alphabet = BECH32;
+    for input_len in 0..40 {
+        roundtrip_random(&alphabet, input_len, 10);
+    }
+}
+
+#[test]
+fn bech32_vectors() {
+    // Same bit packing as RFC 4648 with a different symbol table
+    for (plain, encoded) in [
+        (&b"hello"[..], "dpjkcmr0"),
+        (&b"hello world"[..], "dpjkcmr0ypmk7unvvs"),
+        (&[0xff; 5][..], "llllllll"),
+        (&[0, 1, 2, 3, 4, 5, 6][..], "qqqsyqcyq5rq"),
+    ] {
+        assert_eq!(encoded, encode_alphabet(plain, &BECH32));
+        assert_eq!(plain, &decode_alphabet(encoded, &BECH32).unwrap()[..]);
+    }
+    // Symbols in value order, as in the BIP 173 test vector `abcdef1qpzry9x8gf2tvdw0s3jn54khce6mua7lmqqqxw`
+    let symbols = core::str::from_utf8(BECH32.symbols()).unwrap();
+    assert_eq!(symbols, encode_alphabet(decode_alphabet(symbols, &BECH32).unwrap(), &BECH32));
+}
+
+#[test]
+fn encode_decode_random_rfc_lower_small() {
+    let alphabet = RFC_LOWER;
+    for input_len in 0..40 {
+        roundtrip_random(&alphabet, input_len, 10);
+    }
+}
+
+#[test]
+fn rfc_lower_vectors() {
+    assert_eq!("nbswy3dp", encode_alphabet("hello", &RFC_LOWER));
+    assert_eq!(b"hello", &decode_alphabet("nbswy3dp", &RFC_LOWER).unwrap()[..]);
+    for len in 0..20 {
+        let data: Vec<u8> = (0..len).collect();
         assert_eq!(
-            expected,
-            String::from_utf8(decode(target.as_bytes()).unwrap()).unwrap()
+            encode_alphabet(&data, &RFC).to_ascii_lowercase(),
+            encode_alphabet(&data, &RFC_LOWER)
         );
     }
+}
+
+#[test]
+fn encode_decode_random_dns32_small() {
+    let alphabet = DNS32;
+    for input_len in 0..40 {
+        roundtrip_random(&alphabet, input_len, 10);
+    }
+    assert_eq!(b"hello", &decode_alphabet("NBSWY3DP", &DNS32).unwrap()[..]);
+}
+
+#[test]
+fn encode_decode_random_crockford_small() {
+    let alphabet = CROCKFORD;
+    for input_len in 0..40 {
+        roundtrip_random(&alphabet, input_len, 10);
+    }
+}
 
-    #[test]
-    fn simple_decode_encode() {
-        compare_decode("test", &encode(&decode(&encode(b"test")).unwrap()));
+#[test]
+fn crockford_vectors() {
+    for (plain, encoded) in [
+        ("hello", "D1JPRV3F"),
+        ("test123", "EHJQ6X1H68SG"),
+        ("foobar", "CSQPYRK1E8"),
+    ] {
+        assert_eq!(encoded, encode_alphabet(plain, &CROCKFORD));
+        assert_eq!(plain.as_bytes(), decode_alphabet(encoded, &CROCKFORD).unwrap());
     }
+}
 
-    #[test]
-    fn encode_decode_random_zbase_small() {
-        let alphabet = ZBASE32;
+#[test]
+fn crockford_normalizes_confusables() {
+    assert_eq!(
+        decode_alphabet("0111", &CROCKFORD).unwrap(),
+        decode_alphabet("O1IL", &CROCKFORD).unwrap()
+    );
+    assert_eq!(
+        decode_alphabet("D1JPRV3F", &CROCKFORD).unwrap(),
+        decode_alphabet("d1jprv3f", &CROCKFORD).unwrap()
+    );
+    assert_eq!(
+        decode_alphabet("0111", &CROCKFORD).unwrap(),
+        decode_alphabet("oiLl", &CROCKFORD).unwrap()
+    );
+    // Other alphabets keep their own meaning of these symbols
+    assert_ne!(
+        decode_alphabet("0111", &BASE32HEX).unwrap(),
+        decode_alphabet("O1IL", &BASE32HEX).unwrap()
+    );
+}
+
+#[test]
+fn encode_decode_random_base32hex_small() {
+    let alphabet = BASE32HEX;
+    for input_len in 0..40 {
+        roundtrip_random(&alphabet, input_len, 10);
+    }
+}
+
+#[test]
+fn base32hex_vectors() {
+    // Python's base64.b32hexencode without padding
+    for (plain, encoded) in [
+        ("hello", "D1IMOR3F"),
+        ("foobar", "CPNMUOJ1E8"),
+    ] {
+        assert_eq!(encoded, encode_alphabet(plain, &BASE32HEX));
+        assert_eq!(plain.as_bytes(), decode_alphabet(encoded, &BASE32HEX).unwrap());
+    }
+}
+
+#[test]
+fn encode_decode_random_wordsafe_small() {
+    let alphabet = WORDSAFE;
+    for input_len in 0..40 {
+        roundtrip_random(&alphabet, input_len, 10);
+    }
+}
+
+#[test]
+fn wordsafe_vectors() {
+    for (plain, encoded) in [
+        ("hello", "M3Wgjq5Q"),
+        ("foobar", "JmhgwjX3PC"),
+        ("test123", "PVWh8v3V8CmR"),
+    ] {
+        assert_eq!(encoded, encode_alphabet(plain, &WORDSAFE));
+        assert_eq!(plain.as_bytes(), decode_alphabet(encoded, &WORDSAFE).unwrap());
+    }
+    assert_eq!(
+        Ok(WORDSAFE),
+        AlphabetBuilder::new().symbols("23456789CFGHJMPQRVWXcfghjmpqrvwx").build()
+    );
+    // Upper and lower case symbols have different values
+    assert_ne!(
+        decode_alphabet("M3Wgjq5Q", &WORDSAFE).unwrap(),
+        decode_alphabet("m3wGJQ5q", &WORDSAFE).unwrap()
+    );
+}
+
+#[test]
+fn encode_decode_random_geohash_small() {
+    let alphabet = GEOHASH;
+    for input_len in 0..40 {
+        roundtrip_random(&alphabet, input_len, 10);
+    }
+}
+
+#[test]
+fn geohash_vectors() {
+    for (plain, encoded) in [
+        ("hello", "e1kqsv3g"),
+        ("foobar", "dtrqysm1f8"),
+        ("test123", "fjkr6x1j68th"),
+    ] {
+        assert_eq!(encoded, encode_alphabet(plain, &GEOHASH));
+        assert_eq!(plain.as_bytes(), decode_alphabet(encoded, &GEOHASH).unwrap());
+    }
+    // Geohash excludes a, i, l and o
+    for c in "ailo".bytes() {
+        assert_eq!(Err(DecodeError::InvalidByte(0, c)), decode_alphabet([c, b'0'], &GEOHASH));
+    }
+}
+
+#[test]
+fn encode_decode_random_custom_inversed() {
+    assert_eq!(
+        Ok(ZBASE32),
+        AlphabetBuilder::new()
+            .symbols("ybndrfg8ejkmcpqxot1uwisza345h769")
+            .bit_order(BitOrder::LsbFirst)
+            .build()
+    );
+
+    let alphabet = AlphabetBuilder::new()
+        .symbols("23456789abcdefghjkmnpqrstuvwxyz!")
+        .bit_order(BitOrder::LsbFirst)
+        .build()
+        .unwrap();
+    for input_len in 0..40 {
+        roundtrip_random(&alphabet, input_len, 10);
+    }
+    // Same symbols in normal order produce a different encoding
+    let normal = Alphabet::from_str("23456789abcdefghjkmnpqrstuvwxyz!").unwrap();
+    assert_ne!(encode_alphabet("hello", &normal), encode_alphabet("hello", &alphabet));
+}
+
+#[test]
+fn encode_decode_random_builder_small() {
+    let alphabet = AlphabetBuilder::new()
+        .symbols("0123456789bcdefghjkmnpqrstuvwxyz")
+        .bit_order(BitOrder::LsbFirst)
+        .case_insensitive(true)
+        .build()
+        .unwrap();
+    for input_len in 0..40 {
+        roundtrip_random(&alphabet, input_len, 10);
+    }
+    let encoded = encode_alphabet("hello", &alphabet);
+    assert_eq!(
+        "hello".as_bytes(),
+        decode_alphabet(encoded.to_ascii_uppercase(), &alphabet).unwrap()
+    );
+}
+
+#[test]
+fn packing_orders_unchanged() {
+    // Produced before bit and octet order were split
+    let inputs: [&[u8]; 3] = [b"The quick brown fox", &[0xff; 7], &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10]];
+    for (alphabet, expected) in [
+        (&ZBASE32, ["wn4kgorqim4gsibrnuh6s53pyt36ghb", "99999999999b", "yeyroboyfobqyrrbky"]),
+        (&RFC, ["KRUGKIDROVUWG2ZAMJZG653OEBTG66A", "77777777777Q", "AAAQEAYEAUDAOCAJBI"]),
+    ] {
+        for (input, encoded) in inputs.iter().zip(expected) {
+            assert_eq!(encoded, encode_alphabet(input, alphabet));
+            assert_eq!(*input, &decode_alphabet(encoded, alphabet).unwrap()[..]);
+        }
+    }
+}
+
+#[test]
+fn transcode_between_alphabets() {
+    let plain = b"hello world";
+    let rfc = encode_alphabet(plain, &RFC);
+    let zbase = transcode(&rfc, &RFC, &ZBASE32).unwrap();
+    assert_eq!(encode(plain), zbase);
+    assert_eq!(plain, &decode(&zbase).unwrap()[..]);
+    assert_eq!(Ok(rfc), transcode(&zbase, &ZBASE32, &RFC));
+
+    let reversed = AlphabetBuilder::new()
+        .symbols("ABCDEFGHIJKLMNOPQRSTUVWXYZ234567")
+        .octet_order(OctetOrder::Reversed)
+        .build()
+        .unwrap();
+    for alphabet in SHIPPED {
+        let encoded = transcode(&zbase, &ZBASE32, alphabet).unwrap();
+        let back = transcode(&encoded, alphabet, &reversed).unwrap();
+        assert_eq!(plain, &decode_alphabet(back, &reversed).unwrap()[..]);
+    }
+    assert_eq!(Err(DecodeError::InvalidByte(1, b'!')), transcode("y!", &ZBASE32, &RFC));
+}
+
+#[test]
+fn encode_decode_random_reversed_octets() {
+    for bit_order in [BitOrder::MsbFirst, BitOrder::LsbFirst] {
+        let alphabet = AlphabetBuilder::new()
+            .symbols("ABCDEFGHIJKLMNOPQRSTUVWXYZ234567")
+            .bit_order(bit_order)
+            .octet_order(OctetOrder::Reversed)
+            .build()
+            .unwrap();
         for input_len in 0..40 {
             roundtrip_random(&alphabet, input_len, 10);
         }
+        encoder_state_random_chunks(&alphabet, 50);
     }
 
-    #[test]
-    fn encode_decode_random_zbase_different() {
-        let alphabet = ZBASE32;
-        for input_len in [0, 100, 10, 2, 1024, 32768, 511, 5, 7, 11, 8] {
-            roundtrip_random(&alphabet, input_len, 100);
+    let reversed = AlphabetBuilder::new()
+        .symbols("ABCDEFGHIJKLMNOPQRSTUVWXYZ234567")
+        .octet_order(OctetOrder::Reversed)
+        .build()
+        .unwrap();
+    // Every group is packed last octet first, including the partial one
+    assert_eq!(encode_alphabet("olleh", &RFC), encode_alphabet("hello", &reversed));
+    assert_eq!(
+        encode_alphabet("ollehba", &RFC),
+        encode_alphabet("helloab", &reversed)
+    );
+
+    let mut state = DecoderState::new(&reversed);
+    let mut out = Vec::new();
+    for chunk in encode_alphabet("helloab", &reversed).as_bytes().chunks(3) {
+        state.feed(chunk, &mut out).unwrap();
+    }
+    state.finish(&mut out).unwrap();
+    assert_eq!(b"helloab", &out[..]);
+}
+
+#[test]
+fn encoder_state_random_zbase() {
+    encoder_state_random_chunks(&ZBASE32, 200);
+}
+
+#[test]
+fn encoder_state_random_rfc() {
+    encoder_state_random_chunks(&RFC, 200);
+}
+
+#[test]
+fn encode_ct_same_as_encode_random() {
+    let mut r = rand::rngs::StdRng::from_entropy();
+    let mut out = vec![0_u8; 400];
+    let mut out_ct = vec![0_u8; 400];
+
+    for alphabet in [&ZBASE32, &RFC, &CROCKFORD] {
+        for _ in 0..200 {
+            let input_len = r.gen_range(0..200);
+            let input: Vec<u8> = (0..input_len).map(|_| r.gen::<u8>()).collect();
+            let len = encode_alphabet_slice(&input, &mut out, alphabet);
+            let len_ct = encode_alphabet_slice_ct(&input, &mut out_ct, alphabet);
+
+            assert_eq!(out[..len], out_ct[..len_ct]);
         }
     }
+}
 
-    #[test]
-    fn encode_decode_random_rfc_small() {
-        let alphabet = RFC;
-        for input_len in 0..40 {
-            roundtrip_random(&alphabet, input_len, 10);
+// Bounded versions of the properties checked by the `roundtrip` fuzz target under fuzz/
+
+#[test]
+fn prop_decode_encode_identity() {
+    let mut r = rand::rngs::StdRng::from_entropy();
+
+    for alphabet in SHIPPED {
+        for _ in 0..200 {
+            let input_len = r.gen_range(0..100);
+            let input: Vec<u8> = (0..input_len).map(|_| r.gen::<u8>()).collect();
+            let encoded = encode_alphabet(&input, alphabet);
+
+            assert_eq!(input, decode_alphabet(&encoded, alphabet).unwrap());
         }
     }
+}
 
-    #[test]
-    fn encode_decode_random_bleach_small() {
-        let alphabet = BECH32;
-        for input_len in 0..40 {
-            roundtrip_random(&alphabet, input_len, 10);
+#[test]
+fn prop_encode_decode_stable() {
+    let mut r = rand::rngs::StdRng::from_entropy();
+
+    for alphabet in SHIPPED {
+        // Symbols together with case-folded variants and aliases
+        let accepted: Vec<u8> = (0..=255_u8).filter(|&c| alphabet.contains(c)).collect();
+        for round in 0..400 {
+            let input_len = r.gen_range(0..100);
+            // Every other round uses arbitrary bytes, which must never panic
+            let input: Vec<u8> = if round % 2 == 0 {
+                (0..input_len).map(|_| accepted[r.gen_range(0..accepted.len())]).collect()
+            } else {
+                (0..input_len).map(|_| r.gen::<u8>()).collect()
+            };
+
+            match decode_alphabet(&input, alphabet) {
+                Ok(decoded) => {
+                    let encoded = encode_alphabet(&decoded, alphabet);
+                    assert_eq!(decoded, decode_alphabet(&encoded, alphabet).unwrap());
+                    assert!(validate(&encoded, alphabet).is_ok());
+                }
+                Err(_) => assert!(round % 2 == 1 || !is_valid_encoded_len(input_len)),
+            }
         }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,44 @@
+//! [wasm_bindgen] exports for JavaScript, using the Rspamd compatible [ZBASE32] alphabet
+//!
+//! ~~~js
+//!import { js_encode, js_decode } from "rspamd-base32";
+//!
+//!const encoded = js_encode(new TextEncoder().encode("hello"));
+//!console.assert(encoded === "em3ags7p");
+//!console.assert(new TextDecoder().decode(js_decode(encoded)) === "hello");
+//!~~~
+//!
+//![ZBASE32]: crate::alphabet::ZBASE32
+use crate::decode::decode;
+use crate::encode::encode;
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use wasm_bindgen::prelude::*;
+
+///Encode octets as [crate::encode::encode] does.
+#[wasm_bindgen]
+pub fn js_encode(input: &[u8]) -> String {
+    encode(input)
+}
+
+///Decode a string as [crate::decode::decode] does, throwing an `Error` with the
+///[crate::decode::DecodeError] message on invalid input.
+#[wasm_bindgen]
+pub fn js_decode(input: &str) -> Result<Vec<u8>, JsValue> {
+    decode(input).map_err(|e| JsError::new(&e.to_string()).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::decode::*;
+    use crate::encode::*;
+
+    // JsValue cannot be constructed outside of wasm, check what the bindings delegate to
+    #[test]
+    fn delegates() {
+        assert_eq!("em3ags7py376g3tprd", encode(b"hello world"));
+        assert_eq!(b"hello world".to_vec(), decode("em3ags7py376g3tprd").unwrap());
+        assert_eq!("Invalid byte 33, offset 4.", decode("em3a!s7p").unwrap_err().to_string());
+    }
+}
@@ -0,0 +1,23 @@
+//! Ensures the allocation-free API stays usable from a `no_std` crate
+#![no_std]
+
+// The test harness itself needs std, the code under test must not
+extern crate std;
+
+use rspamd_base32::alphabet::{RFC, ZBASE32};
+use rspamd_base32::{decode_alphabet_slice, decoded_len, encode_alphabet_slice, encoded_len};
+
+#[test]
+fn no_std_roundtrip() {
+    let input = b"hello";
+    let mut encoded = [0_u8; 16];
+    let mut decoded = [0_u8; 16];
+
+    for alphabet in [&ZBASE32, &RFC] {
+        assert!(encoded_len(input.len()).unwrap() <= encoded.len());
+        let enc_len = encode_alphabet_slice(input, &mut encoded, alphabet);
+        assert!(decoded_len(enc_len).unwrap() <= decoded.len());
+        let dec_len = decode_alphabet_slice(&encoded[..enc_len], &mut decoded, alphabet).unwrap();
+        assert_eq!(input, &decoded[..dec_len]);
+    }
+}